@@ -0,0 +1,10 @@
+CREATE TABLE dcc_trust_key (
+    id INTEGER NOT NULL PRIMARY KEY,
+    key_id BLOB NOT NULL,
+    country TEXT,
+    subject TEXT NOT NULL,
+    certificate BLOB NOT NULL UNIQUE,
+    imported_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX dcc_trust_key_key_id_idx ON dcc_trust_key (key_id);
@@ -5,12 +5,22 @@ use eframe::egui::Ui;
 use tokio::sync::RwLock;
 use uuid::Uuid;
 
-use crate::ui::state_worker::StateWorker;
+use crate::{
+    barcode_scanner::{Scan, Symbology},
+    ui::state_worker::StateWorker,
+};
 
 mod aamva;
+mod contact;
+mod dcc;
+mod escape;
 mod generic;
+mod gs1;
 mod link;
 pub mod shc;
+mod wifi;
+
+pub(crate) use generic::GENERIC_DECODER_NAME;
 
 pub trait BarcodeData: Debug + Send + Sync {
     fn id(&self) -> Uuid;
@@ -26,7 +36,29 @@ pub trait BarcodeDecoder: Debug + Send + Sync {
     fn name(&self) -> &'static str;
     fn settings(&self, ui: &mut Ui);
 
+    /// The symbologies this decoder can handle, or `None` if it may be used
+    /// for data from any symbology.
+    fn symbologies(&self) -> Option<&'static [Symbology]> {
+        None
+    }
+
+    /// If this decoder should try text from a scan without a known
+    /// symbology, for decoders limited to specific symbologies.
+    fn accepts_unidentified(&self, _input: &str) -> bool {
+        true
+    }
+
     async fn decode(&self, input: &str) -> eyre::Result<BoxedBarcodeData>;
+
+    /// If this decoder should be given the raw bytes of every scan instead of
+    /// only the text of scans that are valid UTF-8.
+    fn accepts_bytes(&self) -> bool {
+        false
+    }
+
+    async fn decode_bytes(&self, _input: &[u8]) -> eyre::Result<BoxedBarcodeData> {
+        eyre::bail!("decoder does not support binary data")
+    }
 }
 
 pub type BoxedBarcodeDecoder = Box<dyn BarcodeDecoder>;
@@ -40,6 +72,21 @@ pub struct BarcodeDecoders {
 #[derive(Debug)]
 pub enum Action {
     SmartHealthCard(shc::Action),
+    DigitalCovidCertificate(dcc::Action),
+    Contact(contact::Action),
+}
+
+impl Action {
+    /// An error from work a decoder did in the background, with a title for
+    /// showing it.
+    pub fn error(&self) -> Option<(&'static str, &str)> {
+        match self {
+            Self::DigitalCovidCertificate(dcc::Action::TrustListImported(Some(err))) => {
+                Some(("Trust List Error", err))
+            }
+            _ => None,
+        }
+    }
 }
 
 impl BarcodeDecoders {
@@ -78,12 +125,25 @@ impl BarcodeDecoders {
             Box::new(
                 shc::SmartHealthCardDecoder::new(
                     client,
-                    pool,
+                    pool.clone(),
                     state_worker.scoped(Action::SmartHealthCard),
                 )
                 .await?,
             ),
+            Box::new(
+                dcc::DigitalCovidCertificateDecoder::new(
+                    pool,
+                    state_worker.scoped(Action::DigitalCovidCertificate),
+                )
+                .await?,
+            ),
             Box::new(aamva::AamvaDecoder),
+            Box::new(gs1::Gs1Decoder),
+            Box::new(gs1::DigitalLinkDecoder),
+            Box::new(wifi::WifiDecoder),
+            Box::new(contact::ContactDecoder::new(
+                state_worker.scoped(Action::Contact),
+            )),
             Box::new(link::LinkDecoder),
             Box::new(generic::GenericDataDecoder),
         ];
@@ -95,7 +155,7 @@ impl BarcodeDecoders {
     }
 
     #[tracing::instrument(skip(self))]
-    pub async fn decode(&self, input: &str) -> Option<(&'static str, BoxedBarcodeData)> {
+    pub async fn decode(&self, scan: &Scan) -> Option<(&'static str, BoxedBarcodeData)> {
         let disabled_decoders = self.disabled_decoders.read().await;
 
         for decoder in self.decoders.iter() {
@@ -103,7 +163,39 @@ impl BarcodeDecoders {
                 continue;
             }
 
-            match decoder.decode(input).await {
+            if let (Some(symbology), Some(symbologies)) = (scan.symbology(), decoder.symbologies())
+            {
+                if !symbologies.contains(&symbology) {
+                    tracing::trace!(name = decoder.name(), %symbology, "skipping decoder for symbology");
+                    continue;
+                }
+            }
+
+            if let (None, Some(_), Some(text)) =
+                (scan.symbology(), decoder.symbologies(), scan.text())
+            {
+                if !decoder.accepts_unidentified(text) {
+                    tracing::trace!(
+                        name = decoder.name(),
+                        "skipping decoder for unknown symbology"
+                    );
+                    continue;
+                }
+            }
+
+            let result = match scan.text() {
+                _ if decoder.accepts_bytes() => decoder.decode_bytes(scan.data()).await,
+                Some(text) => decoder.decode(text).await,
+                None => {
+                    tracing::trace!(
+                        name = decoder.name(),
+                        "skipping text decoder for binary data"
+                    );
+                    continue;
+                }
+            };
+
+            match result {
                 Ok(data) => return Some((decoder.name(), data)),
                 Err(err) => {
                     tracing::trace!(name = decoder.name(), "could not decode: {err}");
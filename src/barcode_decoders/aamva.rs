@@ -2,6 +2,8 @@ use async_trait::async_trait;
 use eframe::egui::{vec2, CollapsingHeader, Color32, Ui};
 use uuid::Uuid;
 
+use crate::barcode_scanner::Symbology;
+
 use super::{BarcodeData, BarcodeDecoder, BoxedBarcodeData};
 
 #[derive(Debug)]
@@ -15,6 +17,10 @@ impl BarcodeDecoder for AamvaDecoder {
 
     fn settings(&self, _ui: &mut Ui) {}
 
+    fn symbologies(&self) -> Option<&'static [Symbology]> {
+        Some(&[Symbology::Pdf417])
+    }
+
     async fn decode(&self, input: &str) -> eyre::Result<BoxedBarcodeData> {
         let data =
             aamva::parse_barcode(input).map_err(|err| eyre::eyre!("failed to parse: {err}"))?;
@@ -0,0 +1,742 @@
+use std::{
+    fmt::Debug,
+    sync::{Arc, Mutex},
+};
+
+use async_trait::async_trait;
+use eframe::egui::{Color32, Grid, Ui};
+use itertools::Itertools;
+use uuid::Uuid;
+
+use crate::ui::state_worker::StateWorker;
+
+use super::{
+    escape::{split_unescaped, unescape},
+    BarcodeData, BarcodeDecoder, BoxedBarcodeData,
+};
+
+pub(crate) struct ContactDecoder {
+    state_worker: StateWorker<Action>,
+}
+
+impl Debug for ContactDecoder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ContactDecoder").finish_non_exhaustive()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) enum Action {
+    Exported,
+}
+
+impl ContactDecoder {
+    pub(crate) fn new(state_worker: StateWorker<Action>) -> Self {
+        Self { state_worker }
+    }
+}
+
+#[async_trait]
+impl BarcodeDecoder for ContactDecoder {
+    fn name(&self) -> &'static str {
+        "Contact"
+    }
+
+    fn settings(&self, _ui: &mut Ui) {}
+
+    async fn decode(&self, input: &str) -> eyre::Result<BoxedBarcodeData> {
+        let input = input.trim();
+
+        let (contact, vcard) = if starts_with_ignore_case(input, "BEGIN:VCARD") {
+            (parse_vcard(input)?, input.lines().join("\r\n") + "\r\n")
+        } else if starts_with_ignore_case(input, "MECARD:") {
+            let contact = parse_mecard(input)?;
+            let vcard = contact.to_vcard();
+            (contact, vcard)
+        } else {
+            eyre::bail!("not a vcard or mecard");
+        };
+
+        eyre::ensure!(!contact.is_empty(), "contact had no fields");
+
+        Ok(Box::new(ContactData {
+            id: Uuid::new_v4(),
+            contact,
+            vcard,
+            export_error: Default::default(),
+            state_worker: self.state_worker.clone(),
+        }))
+    }
+}
+
+fn starts_with_ignore_case(input: &str, prefix: &str) -> bool {
+    input
+        .get(..prefix.len())
+        .is_some_and(|start| start.eq_ignore_ascii_case(prefix))
+}
+
+/// A value with its type, like a work phone number.
+#[derive(Debug)]
+struct TypedValue {
+    kind: Option<String>,
+    value: String,
+}
+
+#[derive(Debug, Default)]
+struct Contact {
+    name: Option<String>,
+    family_name: Option<String>,
+    given_name: Option<String>,
+    organization: Option<String>,
+    title: Option<String>,
+    phones: Vec<TypedValue>,
+    emails: Vec<TypedValue>,
+    addresses: Vec<TypedValue>,
+    urls: Vec<String>,
+    note: Option<String>,
+}
+
+impl Contact {
+    fn is_empty(&self) -> bool {
+        self.name.is_none()
+            && self.organization.is_none()
+            && self.phones.is_empty()
+            && self.emails.is_empty()
+            && self.addresses.is_empty()
+    }
+
+    /// Build a vCard 3.0 containing this contact.
+    fn to_vcard(&self) -> String {
+        let mut lines = vec!["BEGIN:VCARD".to_string(), "VERSION:3.0".to_string()];
+
+        lines.push(format!(
+            "FN:{}",
+            escape_vcard(self.name.as_deref().unwrap_or_default())
+        ));
+        lines.push(format!(
+            "N:{};{};;;",
+            escape_vcard(self.family_name.as_deref().unwrap_or_default()),
+            escape_vcard(self.given_name.as_deref().unwrap_or_default())
+        ));
+
+        if let Some(organization) = &self.organization {
+            lines.push(format!("ORG:{}", escape_vcard(organization)));
+        }
+
+        if let Some(title) = &self.title {
+            lines.push(format!("TITLE:{}", escape_vcard(title)));
+        }
+
+        for phone in &self.phones {
+            lines.push(format!("TEL:{}", escape_vcard(&phone.value)));
+        }
+
+        for email in &self.emails {
+            lines.push(format!("EMAIL:{}", escape_vcard(&email.value)));
+        }
+
+        for address in &self.addresses {
+            lines.push(format!("ADR:;;{};;;;", escape_vcard(&address.value)));
+        }
+
+        for url in &self.urls {
+            lines.push(format!("URL:{}", escape_vcard(url)));
+        }
+
+        if let Some(note) = &self.note {
+            lines.push(format!("NOTE:{}", escape_vcard(note)));
+        }
+
+        lines.push("END:VCARD".to_string());
+
+        lines.join("\r\n") + "\r\n"
+    }
+}
+
+fn escape_vcard(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('\n', "\\n")
+        .replace(',', "\\,")
+        .replace(';', "\\;")
+}
+
+/// A single property from a vCard.
+#[derive(Debug)]
+struct Property {
+    name: String,
+    types: Vec<String>,
+    /// The value, decoded but still escaped.
+    value: String,
+}
+
+impl Property {
+    fn parse(line: &str) -> eyre::Result<Self> {
+        let separator =
+            find_value_separator(line).ok_or_else(|| eyre::eyre!("property was missing value"))?;
+        let (name, value) = (&line[..separator], &line[separator + 1..]);
+
+        let mut params = name.split(';');
+        let name = params.next().unwrap_or_default();
+        // Properties may be grouped, like `item1.TEL`.
+        let name = name.rsplit('.').next().unwrap_or(name).to_ascii_uppercase();
+
+        let mut types = Vec::new();
+        let mut quoted_printable = false;
+        let mut charset = None;
+
+        for param in params {
+            let (key, value) = match param.split_once('=') {
+                Some((key, value)) => (key.to_ascii_uppercase(), value),
+                // vCard 2.1 allows parameters without names.
+                None if param.eq_ignore_ascii_case("QUOTED-PRINTABLE") => {
+                    ("ENCODING".to_string(), param)
+                }
+                None => ("TYPE".to_string(), param),
+            };
+
+            match key.as_str() {
+                "TYPE" => types.extend(
+                    value
+                        .trim_matches('"')
+                        .split(',')
+                        .map(|kind| kind.to_ascii_lowercase()),
+                ),
+                "ENCODING" => quoted_printable = value.eq_ignore_ascii_case("QUOTED-PRINTABLE"),
+                "CHARSET" => charset = Some(value),
+                _ => (),
+            }
+        }
+
+        // The value is already text, so the charset only applies to the bytes
+        // from quoted-printable data.
+        let value = if quoted_printable {
+            let bytes = decode_quoted_printable(value);
+
+            let encoding = charset
+                .and_then(|charset| encoding_rs::Encoding::for_label(charset.as_bytes()))
+                .unwrap_or(encoding_rs::UTF_8);
+            let (value, _, _) = encoding.decode(&bytes);
+
+            value.into_owned()
+        } else {
+            value.to_string()
+        };
+
+        Ok(Self { name, types, value })
+    }
+
+    /// The value with escapes removed.
+    fn text(&self) -> String {
+        unescape_vcard(&self.value)
+    }
+
+    /// The components of a structured value, like a name or address.
+    fn components(&self) -> Vec<String> {
+        split_unescaped(&self.value, ';')
+            .into_iter()
+            .map(unescape_vcard)
+            .collect()
+    }
+
+    /// The types of the value, excluding ones that aren't useful to show.
+    fn kind(&self) -> Option<String> {
+        let kind = self
+            .types
+            .iter()
+            .filter(|kind| !matches!(kind.as_str(), "pref" | "internet" | "voice" | "x400"))
+            .map(|kind| {
+                let mut chars = kind.chars();
+                chars
+                    .next()
+                    .map(|first| first.to_uppercase().chain(chars).collect::<String>())
+                    .unwrap_or_default()
+            })
+            .join(", ");
+
+        (!kind.is_empty()).then_some(kind)
+    }
+
+    fn typed_value(&self, value: String) -> TypedValue {
+        TypedValue {
+            kind: self.kind(),
+            value,
+        }
+    }
+}
+
+/// Find the colon separating a property's name and parameters from its value,
+/// ignoring colons in quoted parameter values.
+fn find_value_separator(line: &str) -> Option<usize> {
+    let mut quoted = false;
+
+    line.char_indices().find_map(|(index, ch)| match ch {
+        '"' => {
+            quoted = !quoted;
+            None
+        }
+        ':' if !quoted => Some(index),
+        _ => None,
+    })
+}
+
+fn decode_quoted_printable(value: &str) -> Vec<u8> {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+
+    while index < bytes.len() {
+        match bytes[index] {
+            b'=' => {
+                if let Some(byte) = bytes
+                    .get(index + 1..index + 3)
+                    .and_then(|hex| std::str::from_utf8(hex).ok())
+                    .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+                {
+                    decoded.push(byte);
+                    index += 3;
+                } else {
+                    index += 1;
+                }
+            }
+            byte => {
+                decoded.push(byte);
+                index += 1;
+            }
+        }
+    }
+
+    decoded
+}
+
+fn unescape_vcard(value: &str) -> String {
+    let mut unescaped = String::with_capacity(value.len());
+    let mut chars = value.chars();
+
+    while let Some(ch) = chars.next() {
+        match ch {
+            '\\' => match chars.next() {
+                Some('n' | 'N') => unescaped.push('\n'),
+                Some(ch) => unescaped.push(ch),
+                None => (),
+            },
+            ch => unescaped.push(ch),
+        }
+    }
+
+    unescaped
+}
+
+/// Join the lines of a vCard that were folded or continued with soft line
+/// breaks in quoted-printable values.
+fn unfold_lines(input: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    let mut soft_break = false;
+
+    for line in input.lines() {
+        match lines.last_mut() {
+            Some(previous) if soft_break => previous.push_str(line),
+            Some(previous) if line.starts_with([' ', '\t']) => previous.push_str(&line[1..]),
+            _ => lines.push(line.to_string()),
+        }
+
+        let current = lines.last().map(String::as_str).unwrap_or_default();
+        soft_break = current.ends_with('=')
+            && current
+                .split_once(':')
+                .is_some_and(|(name, _)| name.to_ascii_uppercase().contains("QUOTED-PRINTABLE"));
+
+        if soft_break {
+            lines.last_mut().map(String::pop);
+        }
+    }
+
+    lines
+}
+
+/// Parse a vCard 2.1, 3.0, or 4.0 into a contact.
+fn parse_vcard(input: &str) -> eyre::Result<Contact> {
+    let mut contact = Contact::default();
+    let mut structured_name = None;
+
+    for line in unfold_lines(input) {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        // Some generators write stray lines, which shouldn't prevent reading
+        // the rest of the contact.
+        let property = match Property::parse(&line) {
+            Ok(property) => property,
+            Err(err) => {
+                tracing::trace!(line, "skipping malformed line: {err}");
+                continue;
+            }
+        };
+
+        match property.name.as_str() {
+            "FN" => contact.name = Some(property.text()),
+            "N" => {
+                // Names are family, given, additional, prefixes, and suffixes.
+                let components = property.components();
+                contact.family_name = components.first().cloned();
+                contact.given_name = components.get(1).cloned();
+
+                let order = [3, 1, 2, 0, 4];
+                structured_name = Some(
+                    order
+                        .iter()
+                        .filter_map(|index| components.get(*index))
+                        .filter(|component| !component.is_empty())
+                        .join(" "),
+                );
+            }
+            "ORG" => {
+                contact.organization = Some(
+                    property
+                        .components()
+                        .into_iter()
+                        .filter(|component| !component.is_empty())
+                        .join(", "),
+                )
+            }
+            "TITLE" => contact.title = Some(property.text()),
+            "TEL" => {
+                let text = property.text();
+                let number = text.strip_prefix("tel:").unwrap_or(&text).to_string();
+                contact.phones.push(property.typed_value(number));
+            }
+            "EMAIL" => contact.emails.push(property.typed_value(property.text())),
+            "ADR" => {
+                let address = property
+                    .components()
+                    .into_iter()
+                    .filter(|component| !component.is_empty())
+                    .join("\n");
+                contact.addresses.push(property.typed_value(address));
+            }
+            "URL" => contact.urls.push(property.text()),
+            "NOTE" => contact.note = Some(property.text()),
+            _ => (),
+        }
+    }
+
+    contact.name = contact
+        .name
+        .filter(|name| !name.is_empty())
+        .or(structured_name.filter(|name| !name.is_empty()));
+
+    Ok(contact)
+}
+
+/// Parse a MeCard, like `MECARD:N:Doe,John;TEL:5555555555;;`.
+fn parse_mecard(input: &str) -> eyre::Result<Contact> {
+    let data = &input["MECARD:".len()..];
+    let mut contact = Contact::default();
+
+    for field in split_unescaped(data, ';') {
+        let Some((key, value)) = field.split_once(':') else {
+            continue;
+        };
+
+        match key.to_ascii_uppercase().as_str() {
+            "N" => {
+                // Names are written as family name then given name.
+                let parts: Vec<_> = split_unescaped(value, ',')
+                    .into_iter()
+                    .map(unescape)
+                    .collect();
+
+                contact.name = Some(parts.iter().rev().filter(|part| !part.is_empty()).join(" "));
+                contact.family_name = parts.first().cloned();
+                contact.given_name = parts.get(1).cloned();
+            }
+            "ORG" => contact.organization = Some(unescape(value)),
+            "TEL" => contact.phones.push(TypedValue {
+                kind: None,
+                value: unescape(value),
+            }),
+            "EMAIL" => contact.emails.push(TypedValue {
+                kind: None,
+                value: unescape(value),
+            }),
+            "ADR" => contact.addresses.push(TypedValue {
+                kind: None,
+                value: split_unescaped(value, ',')
+                    .into_iter()
+                    .map(unescape)
+                    .filter(|part| !part.is_empty())
+                    .join("\n"),
+            }),
+            "URL" => contact.urls.push(unescape(value)),
+            "NOTE" => contact.note = Some(unescape(value)),
+            _ => (),
+        }
+    }
+
+    Ok(contact)
+}
+
+struct ContactData {
+    id: Uuid,
+    contact: Contact,
+    /// The contact as a vCard, for exporting.
+    vcard: String,
+    export_error: Arc<Mutex<Option<String>>>,
+    state_worker: StateWorker<Action>,
+}
+
+impl Debug for ContactData {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ContactData")
+            .field("id", &self.id)
+            .field("contact", &self.contact)
+            .finish_non_exhaustive()
+    }
+}
+
+impl ContactData {
+    fn export(&self) {
+        let file_name = format!(
+            "{}.vcf",
+            self.contact.name.as_deref().unwrap_or("Contact").trim()
+        );
+        let vcard = self.vcard.clone();
+        let export_error = self.export_error.clone();
+
+        self.state_worker.perform(async move {
+            let Some(file) = rfd::AsyncFileDialog::new()
+                .add_filter("vCard", &["vcf"])
+                .set_file_name(file_name)
+                .save_file()
+                .await
+            else {
+                return Action::Exported;
+            };
+
+            let err = tokio::fs::write(file.path(), vcard).await.err();
+            if let Some(err) = &err {
+                tracing::error!("could not export contact: {err}");
+            }
+
+            *export_error.lock().unwrap() = err.map(|err| err.to_string());
+
+            Action::Exported
+        });
+    }
+
+    fn typed_values(ui: &mut Ui, title: &str, values: &[TypedValue]) {
+        for value in values {
+            ui.strong(title);
+            ui.label(value.kind.as_deref().unwrap_or_default());
+            ui.label(&value.value);
+            ui.end_row();
+        }
+    }
+}
+
+impl BarcodeData for ContactData {
+    fn id(&self) -> Uuid {
+        self.id
+    }
+
+    fn summary(&self) -> String {
+        self.contact
+            .name
+            .clone()
+            .or_else(|| self.contact.organization.clone())
+            .unwrap_or_else(|| "Unnamed Contact".to_string())
+    }
+
+    fn render(&self, ui: &mut Ui) {
+        Grid::new(self.id)
+            .num_columns(3)
+            .striped(true)
+            .spacing([20.0, 4.0])
+            .show(ui, |ui| {
+                if let Some(name) = &self.contact.name {
+                    ui.strong("Name");
+                    ui.label("");
+                    ui.label(name);
+                    ui.end_row();
+                }
+
+                if let Some(organization) = &self.contact.organization {
+                    ui.strong("Organization");
+                    ui.label(self.contact.title.as_deref().unwrap_or_default());
+                    ui.label(organization);
+                    ui.end_row();
+                }
+
+                Self::typed_values(ui, "Phone", &self.contact.phones);
+                Self::typed_values(ui, "Email", &self.contact.emails);
+                Self::typed_values(ui, "Address", &self.contact.addresses);
+
+                for url in &self.contact.urls {
+                    ui.strong("URL");
+                    ui.label("");
+                    ui.hyperlink(url);
+                    ui.end_row();
+                }
+
+                if let Some(note) = &self.contact.note {
+                    ui.strong("Note");
+                    ui.label("");
+                    ui.label(note);
+                    ui.end_row();
+                }
+            });
+
+        ui.horizontal(|ui| {
+            if ui.button("Save as vCard…").clicked() {
+                self.export();
+            }
+
+            if let Some(err) = self.export_error.lock().unwrap().as_deref() {
+                ui.colored_label(Color32::RED, format!("Could not save: {err}"));
+            }
+        });
+    }
+
+    fn raw_data(&self) -> Option<&serde_json::Value> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unfolds_lines() {
+        let input = "BEGIN:VCARD\r\nNOTE:first\r\n  second\r\n\tthird\r\nEND:VCARD";
+
+        assert_eq!(
+            unfold_lines(input),
+            ["BEGIN:VCARD", "NOTE:first secondthird", "END:VCARD"]
+        );
+    }
+
+    #[test]
+    fn unfolds_quoted_printable_soft_breaks() {
+        let input = "NOTE;ENCODING=QUOTED-PRINTABLE:line=\r\n one=0D=0A=\r\ntwo\r\nFN:Name";
+
+        assert_eq!(
+            unfold_lines(input),
+            [
+                "NOTE;ENCODING=QUOTED-PRINTABLE:line one=0D=0Atwo",
+                "FN:Name"
+            ]
+        );
+    }
+
+    #[test]
+    fn decodes_quoted_printable() {
+        assert_eq!(decode_quoted_printable("M=C3=BCller"), "Müller".as_bytes());
+        assert_eq!(decode_quoted_printable("a=3Db=3d"), b"a=b=");
+    }
+
+    #[test]
+    fn applies_charset_to_quoted_printable() {
+        let property =
+            Property::parse("N;CHARSET=ISO-8859-1;ENCODING=QUOTED-PRINTABLE:M=FCller").unwrap();
+        assert_eq!(property.value, "Müller");
+
+        let property = Property::parse("N;CHARSET=ISO-8859-1:Müller").unwrap();
+        assert_eq!(property.value, "Müller");
+    }
+
+    #[test]
+    fn parses_vcard_21() {
+        let input = "BEGIN:VCARD\r\n\
+            VERSION:2.1\r\n\
+            N;CHARSET=UTF-8;ENCODING=QUOTED-PRINTABLE:M=C3=BCller;Anna;;Dr.;\r\n\
+            TEL;CELL:+49 30 1234567\r\n\
+            ADR;WORK;QUOTED-PRINTABLE:;;Hauptstra=C3=9Fe 1=\r\n\
+            ;Berlin;;10115;Germany\r\n\
+            END:VCARD";
+
+        let contact = parse_vcard(input).unwrap();
+
+        assert_eq!(contact.name.as_deref(), Some("Dr. Anna Müller"));
+        assert_eq!(contact.phones[0].kind.as_deref(), Some("Cell"));
+        assert_eq!(contact.phones[0].value, "+49 30 1234567");
+        assert_eq!(
+            contact.addresses[0].value,
+            "Hauptstraße 1\nBerlin\n10115\nGermany"
+        );
+    }
+
+    #[test]
+    fn skips_malformed_vcard_lines() {
+        let input = "BEGIN:VCARD\r\n\
+            VERSION:3.0\r\n\
+            this line has no value\r\n\
+            FN:Jane Doe\r\n\
+            END:VCARD";
+
+        let contact = parse_vcard(input).unwrap();
+
+        assert_eq!(contact.name.as_deref(), Some("Jane Doe"));
+    }
+
+    #[test]
+    fn parses_mecard() {
+        let contact = parse_mecard(
+            "MECARD:N:Doe,John;TEL:5555555555;EMAIL:john@example.com;\
+            ADR:,,123 Main St,Springfield;URL:https://example.com;NOTE:Hi;;",
+        )
+        .unwrap();
+
+        assert_eq!(contact.name.as_deref(), Some("John Doe"));
+        assert_eq!(contact.family_name.as_deref(), Some("Doe"));
+        assert_eq!(contact.given_name.as_deref(), Some("John"));
+        assert_eq!(contact.phones[0].value, "5555555555");
+        assert_eq!(contact.emails[0].value, "john@example.com");
+        assert_eq!(contact.addresses[0].value, "123 Main St\nSpringfield");
+        assert_eq!(contact.urls, ["https://example.com"]);
+        assert_eq!(contact.note.as_deref(), Some("Hi"));
+    }
+
+    #[test]
+    fn parses_escaped_mecard() {
+        let contact =
+            parse_mecard(r"mecard:n:O\,Brien,Pat;org:Smith\; Sons;note:a\:b\\c;invalid;;").unwrap();
+
+        assert_eq!(contact.name.as_deref(), Some("Pat O,Brien"));
+        assert_eq!(contact.organization.as_deref(), Some("Smith; Sons"));
+        assert_eq!(contact.note.as_deref(), Some(r"a:b\c"));
+    }
+
+    #[test]
+    fn converts_mecard_to_vcard() {
+        let contact =
+            parse_mecard(r"MECARD:N:Doe,John;ORG:Smith\, Jones\; Co;TEL:555;NOTE:a\\b;;").unwrap();
+
+        assert_eq!(
+            contact.to_vcard(),
+            "BEGIN:VCARD\r\n\
+            VERSION:3.0\r\n\
+            FN:John Doe\r\n\
+            N:Doe;John;;;\r\n\
+            ORG:Smith\\, Jones\\; Co\r\n\
+            TEL:555\r\n\
+            NOTE:a\\\\b\r\n\
+            END:VCARD\r\n"
+        );
+    }
+
+    #[test]
+    fn round_trips_vcard() {
+        let mut contact = parse_mecard(r"MECARD:N:Doe,John;ADR:1 Main St,Springfield;;").unwrap();
+        contact.note = Some("one, two; three\nfour".to_string());
+
+        let parsed = parse_vcard(&contact.to_vcard()).unwrap();
+
+        assert_eq!(parsed.name, contact.name);
+        assert_eq!(parsed.family_name, contact.family_name);
+        assert_eq!(parsed.given_name, contact.given_name);
+        assert_eq!(parsed.addresses[0].value, "1 Main St\nSpringfield");
+        assert_eq!(parsed.note, contact.note);
+    }
+}
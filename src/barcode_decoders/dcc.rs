@@ -0,0 +1,914 @@
+use std::{
+    fmt::Debug,
+    io::Read,
+    sync::{Arc, Mutex},
+};
+
+use async_trait::async_trait;
+use base64::{
+    engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD},
+    Engine,
+};
+use ciborium::Value;
+use coset::{
+    iana, CborSerializable, CoseSign1, RegisteredLabelWithPrivate, TaggedCborSerializable,
+};
+use eframe::egui::{CollapsingHeader, Color32, Grid, Label, RichText, Ui, Window};
+use egui_extras::{Column, TableBuilder};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+use crate::{barcode_scanner::Symbology, ui::state_worker::StateWorker};
+
+use super::{BarcodeData, BarcodeDecoder, BoxedBarcodeData};
+
+/// Names for the codes used by the EU DCC value sets.
+static VALUE_SETS: phf::Map<&'static str, &'static str> = phf::phf_map! {
+    "840539006" => "COVID-19",
+    "1119305005" => "SARS-CoV-2 antigen vaccine",
+    "1119349007" => "SARS-CoV-2 mRNA vaccine",
+    "J07BX03" => "COVID-19 vaccines",
+    "EU/1/20/1528" => "Comirnaty",
+    "EU/1/20/1507" => "Spikevax",
+    "EU/1/21/1529" => "Vaxzevria",
+    "EU/1/20/1525" => "COVID-19 Vaccine Janssen",
+    "EU/1/21/1618" => "Nuvaxovid",
+    "ORG-100030215" => "BioNTech Manufacturing GmbH",
+    "ORG-100031184" => "Moderna Biotech Spain S.L.",
+    "ORG-100001699" => "AstraZeneca AB",
+    "ORG-100001417" => "Janssen-Cilag International",
+    "ORG-100032020" => "Novavax CZ a.s.",
+    "LP6464-4" => "NAAT",
+    "LP217198-3" => "Rapid Antigen Test",
+    "260415000" => "Not Detected",
+    "260373001" => "Detected",
+};
+
+/// The name of a value set code, or the code if it's unknown.
+fn value_name(code: &str) -> &str {
+    VALUE_SETS.get(code).copied().unwrap_or(code)
+}
+
+pub(crate) struct DigitalCovidCertificateDecoder {
+    pool: SqlitePool,
+    ui_state: Arc<Mutex<UiState>>,
+    state_worker: StateWorker<Action>,
+}
+
+impl Debug for DigitalCovidCertificateDecoder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DigitalCovidCertificateDecoder")
+            .field("ui_state", &self.ui_state)
+            .finish_non_exhaustive()
+    }
+}
+
+#[derive(Debug, Default)]
+struct UiState {
+    showing_trust_list: bool,
+    importing: bool,
+    trust_keys: Vec<TrustKey>,
+}
+
+/// A Document Signer Certificate from the trust list.
+#[derive(Debug, Clone)]
+struct TrustKey {
+    key_id: Vec<u8>,
+    country: Option<String>,
+    subject: String,
+}
+
+impl TrustKey {
+    fn name(&self) -> String {
+        match &self.country {
+            Some(country) => format!("{} ({country})", self.subject),
+            None => self.subject.clone(),
+        }
+    }
+}
+
+/// A trust list in the format published by the DCC gateway.
+#[derive(Debug, Deserialize)]
+struct TrustListJson {
+    certificates: Vec<TrustListCertificate>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TrustListCertificate {
+    #[serde(rename = "rawData")]
+    raw_data: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct HealthCertificate {
+    #[serde(rename = "nam")]
+    name: PersonName,
+    #[serde(rename = "dob")]
+    date_of_birth: String,
+    #[serde(default, rename = "v")]
+    vaccinations: Vec<Vaccination>,
+    #[serde(default, rename = "t")]
+    tests: Vec<Test>,
+    #[serde(default, rename = "r")]
+    recoveries: Vec<Recovery>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PersonName {
+    #[serde(rename = "fn")]
+    family: Option<String>,
+    #[serde(rename = "gn")]
+    given: Option<String>,
+    #[serde(rename = "fnt")]
+    family_transliterated: String,
+    #[serde(rename = "gnt")]
+    given_transliterated: Option<String>,
+}
+
+impl PersonName {
+    fn full_name(&self) -> String {
+        match (&self.given, &self.family) {
+            (Some(given), Some(family)) => format!("{given} {family}"),
+            (None, Some(family)) => family.clone(),
+            _ => [
+                self.given_transliterated.as_deref(),
+                Some(self.family_transliterated.as_str()),
+            ]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>()
+            .join(" "),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct Vaccination {
+    #[serde(rename = "tg")]
+    target: String,
+    #[serde(rename = "mp")]
+    product: String,
+    #[serde(rename = "ma")]
+    manufacturer: String,
+    #[serde(rename = "dn")]
+    dose_number: u32,
+    #[serde(rename = "sd")]
+    total_doses: u32,
+    #[serde(rename = "dt")]
+    date: String,
+    #[serde(rename = "co")]
+    country: String,
+    #[serde(rename = "ci")]
+    identifier: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Test {
+    #[serde(rename = "tg")]
+    target: String,
+    #[serde(rename = "tt")]
+    test_type: String,
+    #[serde(rename = "sc")]
+    sample_collected: String,
+    #[serde(rename = "tr")]
+    result: String,
+    #[serde(rename = "tc")]
+    testing_centre: Option<String>,
+    #[serde(rename = "co")]
+    country: String,
+    #[serde(rename = "ci")]
+    identifier: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Recovery {
+    #[serde(rename = "tg")]
+    target: String,
+    #[serde(rename = "fr")]
+    first_positive: String,
+    #[serde(rename = "df")]
+    valid_from: String,
+    #[serde(rename = "du")]
+    valid_until: String,
+    #[serde(rename = "co")]
+    country: String,
+    #[serde(rename = "ci")]
+    identifier: String,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) enum Action {
+    TrustListImporting,
+    /// The import finished, with an error if it failed.
+    TrustListImported(Option<String>),
+}
+
+impl DigitalCovidCertificateDecoder {
+    /// The CWT claim containing the health certificate.
+    const HCERT_CLAIM: i64 = -260;
+
+    pub(crate) async fn new(
+        pool: SqlitePool,
+        state_worker: StateWorker<Action>,
+    ) -> eyre::Result<Self> {
+        let trust_keys = Self::load_trust_keys(&pool).await?;
+        tracing::debug!(keys = trust_keys.len(), "loaded dcc trust list");
+
+        let ui_state = Arc::new(Mutex::new(UiState {
+            trust_keys,
+            ..Default::default()
+        }));
+
+        Ok(Self {
+            pool,
+            ui_state,
+            state_worker,
+        })
+    }
+
+    async fn load_trust_keys(pool: &SqlitePool) -> eyre::Result<Vec<TrustKey>> {
+        sqlx::query_as!(
+            TrustKey,
+            "SELECT key_id, country, subject FROM dcc_trust_key ORDER BY country, subject"
+        )
+        .fetch_all(pool)
+        .await
+        .map_err(Into::into)
+    }
+
+    fn import_trust_list(&self) {
+        let pool = self.pool.clone();
+        let ui_state = self.ui_state.clone();
+        let state_worker = self.state_worker.clone();
+
+        self.state_worker.perform(async move {
+            let Some(file) = rfd::AsyncFileDialog::new()
+                .add_filter("Trust Lists", &["json", "pem", "crt", "cer", "der"])
+                .pick_file()
+                .await
+            else {
+                return Action::TrustListImported(None);
+            };
+
+            ui_state.lock().unwrap().importing = true;
+            state_worker.send(Action::TrustListImporting);
+
+            let result = Self::save_trust_list(&pool, &file.read().await).await;
+
+            let mut ui_state = ui_state.lock().unwrap();
+            ui_state.importing = false;
+
+            match result {
+                Ok(trust_keys) => {
+                    ui_state.trust_keys = trust_keys;
+                    Action::TrustListImported(None)
+                }
+                Err(err) => {
+                    tracing::error!("could not import trust list: {err}");
+                    Action::TrustListImported(Some(err.to_string()))
+                }
+            }
+        });
+    }
+
+    /// Save every certificate in a trust list, returning the updated list of
+    /// keys.
+    async fn save_trust_list(pool: &SqlitePool, data: &[u8]) -> eyre::Result<Vec<TrustKey>> {
+        let certificates = parse_trust_list(data)?;
+        tracing::info!(count = certificates.len(), "importing dcc trust list");
+
+        let mut tx = pool.begin().await?;
+
+        for certificate in certificates {
+            let (_, parsed) = x509_parser::parse_x509_certificate(&certificate)?;
+
+            let key_id = &Sha256::digest(&certificate)[..8];
+            let country = parsed
+                .subject()
+                .iter_country()
+                .next()
+                .and_then(|country| country.as_str().ok());
+            let subject = parsed
+                .subject()
+                .iter_common_name()
+                .next()
+                .and_then(|name| name.as_str().ok())
+                .map(ToString::to_string)
+                .unwrap_or_else(|| parsed.subject().to_string());
+
+            sqlx::query!(
+                "INSERT INTO dcc_trust_key (key_id, country, subject, certificate)
+                    VALUES ($1, $2, $3, $4) ON CONFLICT (certificate) DO NOTHING",
+                key_id,
+                country,
+                subject,
+                certificate
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+
+        Self::load_trust_keys(pool).await
+    }
+
+    fn decode_qr_data(input: &str) -> eyre::Result<CoseSign1> {
+        let data = input
+            .trim()
+            .strip_prefix("HC1:")
+            .ok_or_else(|| eyre::eyre!("missing HC1 prefix"))?;
+
+        let data = base45::decode(data)?;
+
+        // Compression is recommended but not required, so only inflate data
+        // with a zlib header.
+        let data = if data.first() == Some(&0x78) {
+            let mut decompressed_data = Vec::new();
+            flate2::read::ZlibDecoder::new(data.as_slice()).read_to_end(&mut decompressed_data)?;
+            decompressed_data
+        } else {
+            data
+        };
+
+        CoseSign1::from_tagged_slice(&data)
+            .or_else(|_| CoseSign1::from_slice(&data))
+            .map_err(|err| eyre::eyre!("invalid cose message: {err:?}"))
+    }
+
+    /// Check the signature against every trust list key with a matching key
+    /// ID, returning the key that signed it.
+    async fn verify(&self, sign1: &CoseSign1) -> eyre::Result<(Option<TrustKey>, bool)> {
+        let key_id = if sign1.protected.header.key_id.is_empty() {
+            &sign1.unprotected.key_id
+        } else {
+            &sign1.protected.header.key_id
+        };
+
+        let algorithm = match &sign1.protected.header.alg {
+            Some(RegisteredLabelWithPrivate::Assigned(iana::Algorithm::ES256)) => {
+                jsonwebtoken::Algorithm::ES256
+            }
+            Some(RegisteredLabelWithPrivate::Assigned(iana::Algorithm::PS256)) => {
+                jsonwebtoken::Algorithm::PS256
+            }
+            alg => eyre::bail!("unsupported algorithm: {alg:?}"),
+        };
+
+        let keys = sqlx::query!(
+            "SELECT key_id, country, subject, certificate FROM dcc_trust_key WHERE key_id = $1",
+            key_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        if keys.is_empty() {
+            tracing::warn!(key_id = hex::encode(key_id), "unable to find dcc trust key");
+        }
+
+        let mut signer = None;
+
+        for key in keys {
+            let trust_key = TrustKey {
+                key_id: key.key_id,
+                country: key.country,
+                subject: key.subject,
+            };
+
+            let verified = sign1
+                .verify_signature(b"", |signature, data| {
+                    verify_signature(&key.certificate, algorithm, signature, data)
+                })
+                .is_ok();
+
+            if verified {
+                return Ok((Some(trust_key), true));
+            }
+
+            signer = Some(trust_key);
+        }
+
+        Ok((signer, false))
+    }
+}
+
+/// Get the entry of a CBOR map with an integer key.
+fn map_get(value: &Value, key: i64) -> Option<&Value> {
+    value.as_map()?.iter().find_map(|(entry_key, entry_value)| {
+        (entry_key.as_integer() == Some(key.into())).then_some(entry_value)
+    })
+}
+
+/// Get a CWT claim containing a timestamp.
+fn timestamp_claim(claims: &Value, key: i64) -> Option<time::OffsetDateTime> {
+    let timestamp = i64::try_from(map_get(claims, key)?.as_integer()?).ok()?;
+
+    time::OffsetDateTime::from_unix_timestamp(timestamp).ok()
+}
+
+/// Verify a signature using the public key of a DER encoded certificate.
+fn verify_signature(
+    certificate: &[u8],
+    algorithm: jsonwebtoken::Algorithm,
+    signature: &[u8],
+    data: &[u8],
+) -> eyre::Result<()> {
+    let (_, certificate) = x509_parser::parse_x509_certificate(certificate)?;
+    let public_key = &certificate.public_key().subject_public_key.data;
+
+    let key = match algorithm {
+        jsonwebtoken::Algorithm::ES256 => jsonwebtoken::DecodingKey::from_ec_der(public_key),
+        _ => jsonwebtoken::DecodingKey::from_rsa_der(public_key),
+    };
+
+    eyre::ensure!(
+        jsonwebtoken::crypto::verify(&URL_SAFE_NO_PAD.encode(signature), data, &key, algorithm)?,
+        "signature did not match"
+    );
+
+    Ok(())
+}
+
+/// Extract DER encoded certificates from a trust list.
+///
+/// Trust lists may be PEM certificates, a single DER certificate, or JSON from
+/// the DCC gateway, optionally preceded by a signature line.
+fn parse_trust_list(data: &[u8]) -> eyre::Result<Vec<Vec<u8>>> {
+    // DER data may contain anything, so check for it before looking for text.
+    if data.first() == Some(&0x30) && x509_parser::parse_x509_certificate(data).is_ok() {
+        return Ok(vec![data.to_vec()]);
+    }
+
+    let text = data.trim_ascii_start();
+
+    let certificates: Vec<_> = if text.starts_with(b"-----BEGIN") {
+        x509_parser::pem::Pem::iter_from_buffer(text)
+            .map(|pem| pem.map(|pem| pem.contents))
+            .collect::<Result<_, _>>()?
+    } else {
+        // The gateway may put the signature on its own line before the JSON.
+        let json = match text.iter().position(|b| *b == b'\n') {
+            Some(end) if !text.starts_with(b"{") => &text[end + 1..],
+            _ => text,
+        };
+
+        let trust_list: TrustListJson = serde_json::from_slice(json)?;
+
+        trust_list
+            .certificates
+            .into_iter()
+            .map(|certificate| STANDARD.decode(certificate.raw_data))
+            .collect::<Result<_, _>>()?
+    };
+
+    eyre::ensure!(!certificates.is_empty(), "trust list had no certificates");
+
+    Ok(certificates)
+}
+
+#[async_trait]
+impl BarcodeDecoder for DigitalCovidCertificateDecoder {
+    fn name(&self) -> &'static str {
+        "EU Digital COVID Certificate"
+    }
+
+    fn settings(&self, ui: &mut Ui) {
+        let mut ui_state = self.ui_state.lock().unwrap();
+
+        ui.separator();
+
+        if ui_state.importing {
+            ui.horizontal(|ui| {
+                ui.label("Importing Trust List");
+                ui.spinner();
+            });
+        } else {
+            ui.label(format!("DCC Trust List Keys {}", ui_state.trust_keys.len()));
+
+            if ui.button("Import Trust List…").clicked() {
+                self.import_trust_list();
+            }
+        }
+
+        if ui.button("DCC Trust List Database").clicked() {
+            ui_state.showing_trust_list = true;
+        }
+
+        let mut showing = ui_state.showing_trust_list;
+
+        Window::new("DCC Trust List Database")
+            .open(&mut showing)
+            .resizable(true)
+            .default_width(600.0)
+            .show(ui.ctx(), |ui| {
+                TableBuilder::new(ui)
+                    .column(Column::initial(60.0))
+                    .column(Column::initial(120.0))
+                    .column(Column::remainder())
+                    .auto_shrink([false, false])
+                    .header(18.0, |mut header| {
+                        header.col(|ui| {
+                            ui.heading("Country");
+                        });
+
+                        header.col(|ui| {
+                            ui.heading("Key ID");
+                        });
+
+                        header.col(|ui| {
+                            ui.heading("Subject");
+                        });
+                    })
+                    .body(|body| {
+                        body.rows(18.0, ui_state.trust_keys.len(), |mut row| {
+                            let key = &ui_state.trust_keys[row.index()];
+
+                            row.col(|ui| {
+                                ui.label(key.country.as_deref().unwrap_or_default());
+                            });
+
+                            row.col(|ui| {
+                                ui.monospace(STANDARD.encode(&key.key_id));
+                            });
+
+                            row.col(|ui| {
+                                ui.label(&key.subject);
+                            });
+                        });
+                    });
+            });
+
+        ui_state.showing_trust_list = showing;
+    }
+
+    fn symbologies(&self) -> Option<&'static [Symbology]> {
+        Some(&[Symbology::QrCode, Symbology::Aztec])
+    }
+
+    async fn decode(&self, input: &str) -> eyre::Result<BoxedBarcodeData> {
+        let sign1 = Self::decode_qr_data(input)?;
+
+        let payload = sign1
+            .payload
+            .as_deref()
+            .ok_or_else(|| eyre::eyre!("cose message was missing payload"))?;
+        let claims: Value = ciborium::de::from_reader(payload)?;
+
+        let hcert = map_get(&claims, Self::HCERT_CLAIM)
+            .and_then(|hcert| map_get(hcert, 1))
+            .ok_or_else(|| eyre::eyre!("payload was missing health certificate"))?;
+        tracing::trace!(?hcert, "extracted data");
+
+        let certificate: HealthCertificate = hcert.deserialized()?;
+
+        // Certificates that can't be verified are still shown, just not as
+        // verified.
+        let (signer, verified) = match self.verify(&sign1).await {
+            Ok(result) => result,
+            Err(err) => {
+                tracing::warn!("could not verify digital covid certificate: {err}");
+                (None, false)
+            }
+        };
+        tracing::info!(verified, "processed digital covid certificate");
+
+        Ok(Box::new(DigitalCovidCertificateData {
+            id: Uuid::new_v4(),
+            verified,
+            signer,
+            issuer: map_get(&claims, 1)
+                .and_then(Value::as_text)
+                .map(ToString::to_string),
+            expires_at: timestamp_claim(&claims, 4),
+            certificate,
+            raw_data: serde_json::to_value(hcert)?,
+        }))
+    }
+}
+
+#[derive(Debug)]
+struct DigitalCovidCertificateData {
+    id: Uuid,
+    verified: bool,
+    signer: Option<TrustKey>,
+    /// The country that issued the certificate.
+    issuer: Option<String>,
+    expires_at: Option<time::OffsetDateTime>,
+    certificate: HealthCertificate,
+    raw_data: serde_json::Value,
+}
+
+impl DigitalCovidCertificateData {
+    fn verified_widget(&self, ui: &mut Ui) {
+        match &self.signer {
+            Some(signer) if self.verified => {
+                ui.add(Label::new(
+                    RichText::new(format!("✅ Verified by {}", signer.name()))
+                        .color(Color32::GREEN),
+                ));
+            }
+            Some(signer) => {
+                ui.add(Label::new(
+                    RichText::new(format!("❌ NOT Verified by {}", signer.name()))
+                        .color(Color32::RED),
+                ));
+            }
+            None => {
+                ui.add(Label::new(
+                    RichText::new("❌ NOT Verified").color(Color32::RED),
+                ))
+                .on_hover_text("No key in the trust list matched this certificate.");
+            }
+        }
+    }
+}
+
+impl BarcodeData for DigitalCovidCertificateData {
+    fn id(&self) -> Uuid {
+        self.id
+    }
+
+    fn summary(&self) -> String {
+        self.certificate.name.full_name()
+    }
+
+    fn render(&self, ui: &mut Ui) {
+        self.verified_widget(ui);
+
+        if let Some(expires_at) = self.expires_at {
+            let text = format!("Expires {}", expires_at.date());
+
+            if expires_at < time::OffsetDateTime::now_utc() {
+                ui.colored_label(Color32::YELLOW, format!("⏰ {text}"));
+            } else {
+                ui.label(text);
+            }
+        }
+
+        Grid::new(self.id)
+            .num_columns(3)
+            .striped(true)
+            .spacing([40.0, 4.0])
+            .show(ui, |ui| {
+                ui.strong("Person");
+                ui.label(self.issuer.as_deref().unwrap_or("Unknown Issuer"));
+                ui.vertical(|ui| {
+                    ui.strong(self.certificate.name.full_name());
+                    ui.label(format!("🎂 {}", self.certificate.date_of_birth));
+                });
+                ui.end_row();
+
+                for vaccination in &self.certificate.vaccinations {
+                    ui.strong("Vaccination");
+                    ui.label(&vaccination.identifier);
+                    ui.vertical(|ui| {
+                        ui.strong(format!(
+                            "{} — {}",
+                            value_name(&vaccination.target),
+                            value_name(&vaccination.product)
+                        ));
+                        ui.label(format!(
+                            "{} — {}",
+                            value_name(&vaccination.manufacturer),
+                            vaccination.country
+                        ));
+                        ui.label(format!(
+                            "Dose {}/{} {}",
+                            vaccination.dose_number, vaccination.total_doses, vaccination.date
+                        ));
+                    });
+                    ui.end_row();
+                }
+
+                for test in &self.certificate.tests {
+                    ui.strong("Test");
+                    ui.label(&test.identifier);
+                    ui.vertical(|ui| {
+                        ui.strong(format!(
+                            "{} — {}",
+                            value_name(&test.target),
+                            value_name(&test.result)
+                        ));
+
+                        if let Some(testing_centre) = &test.testing_centre {
+                            ui.label(format!("{testing_centre} — {}", test.country));
+                        } else {
+                            ui.label(&test.country);
+                        }
+
+                        ui.label(format!(
+                            "{} {}",
+                            value_name(&test.test_type),
+                            test.sample_collected
+                        ));
+                    });
+                    ui.end_row();
+                }
+
+                for recovery in &self.certificate.recoveries {
+                    ui.strong("Recovery");
+                    ui.label(&recovery.identifier);
+                    ui.vertical(|ui| {
+                        ui.strong(value_name(&recovery.target));
+                        ui.label(format!(
+                            "First Positive {} — {}",
+                            recovery.first_positive, recovery.country
+                        ));
+                        ui.label(format!(
+                            "Valid {} to {}",
+                            recovery.valid_from, recovery.valid_until
+                        ));
+                    });
+                    ui.end_row();
+                }
+            });
+
+        CollapsingHeader::new("Raw Data")
+            .id_source(format!("{}-data", self.id))
+            .show(ui, |ui| {
+                let theme = egui_extras::syntax_highlighting::CodeTheme::from_memory(ui.ctx());
+                egui_extras::syntax_highlighting::code_view_ui(
+                    ui,
+                    &theme,
+                    &serde_json::to_string_pretty(&self.raw_data)
+                        .expect("could not reserialize data"),
+                    "json",
+                );
+            });
+    }
+
+    fn raw_data(&self) -> Option<&serde_json::Value> {
+        Some(&self.raw_data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A test case in the layout of the EU dgc-testdata repository.
+    #[derive(Debug, Deserialize)]
+    #[serde(rename_all = "UPPERCASE")]
+    struct TestVector {
+        cose: String,
+        prefix: String,
+        #[serde(rename = "TESTCTX")]
+        context: TestContext,
+    }
+
+    #[derive(Debug, Deserialize)]
+    #[serde(rename_all = "UPPERCASE")]
+    struct TestContext {
+        certificate: String,
+    }
+
+    impl TestVector {
+        fn certificate(&self) -> Vec<u8> {
+            STANDARD.decode(&self.context.certificate).unwrap()
+        }
+    }
+
+    fn vector(data: &str) -> TestVector {
+        serde_json::from_str(data).unwrap()
+    }
+
+    fn es256() -> TestVector {
+        vector(include_str!("../../tests/fixtures/dcc/es256.json"))
+    }
+
+    fn ps256() -> TestVector {
+        vector(include_str!("../../tests/fixtures/dcc/ps256.json"))
+    }
+
+    fn verify_with(sign1: &CoseSign1, certificate: &[u8]) -> eyre::Result<()> {
+        let algorithm = match sign1.protected.header.alg {
+            Some(RegisteredLabelWithPrivate::Assigned(iana::Algorithm::ES256)) => {
+                jsonwebtoken::Algorithm::ES256
+            }
+            _ => jsonwebtoken::Algorithm::PS256,
+        };
+
+        sign1.verify_signature(b"", |signature, data| {
+            verify_signature(certificate, algorithm, signature, data)
+        })
+    }
+
+    #[test]
+    fn decodes_qr_data() {
+        for vector in [es256(), ps256()] {
+            let sign1 = DigitalCovidCertificateDecoder::decode_qr_data(&vector.prefix).unwrap();
+            let cose = hex::decode(&vector.cose).unwrap();
+
+            // The compressed vector uses a tagged message, the uncompressed
+            // one doesn't.
+            let expected = CoseSign1::from_tagged_slice(&cose)
+                .or_else(|_| CoseSign1::from_slice(&cose))
+                .unwrap();
+            assert_eq!(sign1, expected);
+
+            let claims: Value =
+                ciborium::de::from_reader(sign1.payload.as_deref().unwrap()).unwrap();
+            assert_eq!(map_get(&claims, 1).and_then(Value::as_text), Some("DE"));
+
+            let hcert = map_get(&claims, DigitalCovidCertificateDecoder::HCERT_CLAIM)
+                .and_then(|hcert| map_get(hcert, 1))
+                .unwrap();
+            let certificate: HealthCertificate = hcert.deserialized().unwrap();
+            assert_eq!(certificate.name.full_name(), "Erika Mustermann");
+            assert_eq!(certificate.vaccinations[0].product, "EU/1/20/1507");
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_qr_data() {
+        let vector = es256();
+
+        let without_prefix = vector.prefix.strip_prefix("HC1:").unwrap();
+        assert!(DigitalCovidCertificateDecoder::decode_qr_data(without_prefix).is_err());
+
+        // Lowercase characters aren't in the base45 alphabet.
+        assert!(DigitalCovidCertificateDecoder::decode_qr_data("HC1:abc").is_err());
+
+        let truncated = &vector.prefix[..vector.prefix.len() / 2];
+        assert!(DigitalCovidCertificateDecoder::decode_qr_data(truncated).is_err());
+    }
+
+    #[test]
+    fn parses_der_trust_list() {
+        let certificate = es256().certificate();
+        assert_eq!(parse_trust_list(&certificate).unwrap(), [certificate]);
+    }
+
+    #[test]
+    fn parses_pem_trust_list() {
+        let certificates = [es256().certificate(), ps256().certificate()];
+
+        let pem: String = certificates
+            .iter()
+            .map(|certificate| {
+                format!(
+                    "-----BEGIN CERTIFICATE-----\n{}\n-----END CERTIFICATE-----\n",
+                    STANDARD.encode(certificate)
+                )
+            })
+            .collect();
+
+        assert_eq!(
+            parse_trust_list(format!("\n  {pem}").as_bytes()).unwrap(),
+            certificates
+        );
+    }
+
+    #[test]
+    fn parses_gateway_trust_list() {
+        let certificates = [es256().certificate(), ps256().certificate()];
+
+        let json = serde_json::json!({
+            "certificates": certificates
+                .iter()
+                .map(|certificate| serde_json::json!({
+                    "certificateType": "DSC",
+                    "country": "DE",
+                    "kid": STANDARD.encode(&Sha256::digest(certificate)[..8]),
+                    "rawData": STANDARD.encode(certificate),
+                }))
+                .collect::<Vec<_>>(),
+        });
+
+        assert_eq!(
+            parse_trust_list(json.to_string().as_bytes()).unwrap(),
+            certificates
+        );
+
+        let signed = format!("MEUCIQDtrustlistsignature==\n{json}");
+        assert_eq!(parse_trust_list(signed.as_bytes()).unwrap(), certificates);
+    }
+
+    #[test]
+    fn rejects_empty_trust_list() {
+        assert!(parse_trust_list(br#"{"certificates":[]}"#).is_err());
+        assert!(parse_trust_list(b"").is_err());
+    }
+
+    #[test]
+    fn verifies_signatures() {
+        for vector in [es256(), ps256()] {
+            let sign1 = DigitalCovidCertificateDecoder::decode_qr_data(&vector.prefix).unwrap();
+            verify_with(&sign1, &vector.certificate()).unwrap();
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_signatures() {
+        let vector = es256();
+        let mut sign1 = DigitalCovidCertificateDecoder::decode_qr_data(&vector.prefix).unwrap();
+
+        // Signed by a different key.
+        assert!(verify_with(&sign1, &ps256().certificate()).is_err());
+
+        sign1.payload.as_mut().unwrap()[2] ^= 0x01;
+        assert!(verify_with(&sign1, &vector.certificate()).is_err());
+    }
+}
@@ -0,0 +1,76 @@
+/// Split data on a separator that isn't escaped by a backslash.
+pub(super) fn split_unescaped(data: &str, separator: char) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut start = 0;
+    let mut escaped = false;
+
+    for (index, ch) in data.char_indices() {
+        match ch {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            ch if ch == separator => {
+                parts.push(&data[start..index]);
+                start = index + ch.len_utf8();
+            }
+            _ => (),
+        }
+    }
+
+    parts.push(&data[start..]);
+    parts
+}
+
+/// Remove backslash escapes and quotes surrounding the value, as used by
+/// MeCard and similar formats.
+pub(super) fn unescape(value: &str) -> String {
+    let value = match value.strip_prefix('"').and_then(|v| v.strip_suffix('"')) {
+        Some(value) if !value.ends_with('\\') => value,
+        _ => value,
+    };
+
+    let mut unescaped = String::with_capacity(value.len());
+    let mut chars = value.chars();
+
+    while let Some(ch) = chars.next() {
+        match ch {
+            '\\' => unescaped.extend(chars.next()),
+            ch => unescaped.push(ch),
+        }
+    }
+
+    unescaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_unescaped() {
+        assert_eq!(split_unescaped("a;b;;c", ';'), ["a", "b", "", "c"]);
+        assert_eq!(split_unescaped(r"a\;b;c", ';'), [r"a\;b", "c"]);
+        assert_eq!(split_unescaped(r"a\\;b", ';'), [r"a\\", "b"]);
+        assert_eq!(split_unescaped(r"a\:b:c", ':'), [r"a\:b", "c"]);
+        assert_eq!(split_unescaped("", ';'), [""]);
+        assert_eq!(split_unescaped("é;ü", ';'), ["é", "ü"]);
+    }
+
+    #[test]
+    fn unescapes() {
+        assert_eq!(unescape(r"a\;b\:c\,d"), "a;b:c,d");
+        assert_eq!(unescape(r"back\\slash"), r"back\slash");
+        assert_eq!(unescape(r#"say \"hi\""#), r#"say "hi""#);
+        assert_eq!(unescape(r"trailing\"), "trailing");
+    }
+
+    #[test]
+    fn removes_quotes() {
+        assert_eq!(unescape(r#""quoted""#), "quoted");
+        assert_eq!(unescape(r#""a;b""#), "a;b");
+        assert_eq!(unescape(r#""""#), "");
+
+        // The closing quote is escaped, so the value isn't quoted.
+        assert_eq!(unescape(r#""open\""#), r#""open""#);
+        assert_eq!(unescape(r#""unbalanced"#), r#""unbalanced"#);
+    }
+}
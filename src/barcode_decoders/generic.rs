@@ -5,13 +5,16 @@ use uuid::Uuid;
 
 use super::{BarcodeData, BarcodeDecoder, BoxedBarcodeData};
 
+/// The name of the decoder used when no other decoder matched.
+pub(crate) const GENERIC_DECODER_NAME: &str = "Generic Data";
+
 #[derive(Debug)]
 pub(crate) struct GenericDataDecoder;
 
 #[async_trait]
 impl BarcodeDecoder for GenericDataDecoder {
     fn name(&self) -> &'static str {
-        "Generic Data"
+        GENERIC_DECODER_NAME
     }
 
     fn settings(&self, _ui: &mut Ui) {}
@@ -20,14 +23,55 @@ impl BarcodeDecoder for GenericDataDecoder {
         Ok(Box::new(GenericData {
             id: Uuid::new_v4(),
             data: input.to_string(),
+            binary_len: None,
+        }))
+    }
+
+    fn accepts_bytes(&self) -> bool {
+        true
+    }
+
+    async fn decode_bytes(&self, input: &[u8]) -> eyre::Result<BoxedBarcodeData> {
+        if let Ok(input) = std::str::from_utf8(input) {
+            return self.decode(input).await;
+        }
+
+        Ok(Box::new(GenericData {
+            id: Uuid::new_v4(),
+            data: hex_dump(input),
+            binary_len: Some(input.len()),
         }))
     }
 }
 
+/// Format bytes as rows of hex values followed by their printable characters.
+fn hex_dump(input: &[u8]) -> String {
+    input
+        .chunks(16)
+        .enumerate()
+        .map(|(index, chunk)| {
+            let hex = chunk.iter().map(|byte| format!("{byte:02x}")).join(" ");
+            let printable: String = chunk
+                .iter()
+                .map(|byte| {
+                    if byte.is_ascii_graphic() || *byte == b' ' {
+                        *byte as char
+                    } else {
+                        '.'
+                    }
+                })
+                .collect();
+
+            format!("{:08x}  {hex:<47}  {printable}", index * 16)
+        })
+        .join("\n")
+}
+
 #[derive(Debug)]
 struct GenericData {
     id: Uuid,
     data: String,
+    binary_len: Option<usize>,
 }
 
 impl BarcodeData for GenericData {
@@ -36,6 +80,10 @@ impl BarcodeData for GenericData {
     }
 
     fn summary(&self) -> String {
+        if let Some(len) = self.binary_len {
+            return format!("Binary Data ({len} bytes)");
+        }
+
         let trimmed_data = self.data.trim();
 
         let mut display = trimmed_data
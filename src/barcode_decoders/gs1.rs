@@ -0,0 +1,446 @@
+use async_trait::async_trait;
+use eframe::egui::{CollapsingHeader, Color32, Grid, RichText, Ui};
+use uuid::Uuid;
+
+use crate::barcode_scanner::Symbology;
+
+use self::ai::{AiDefinition, Content, Kind, Length};
+
+use super::{BarcodeData, BarcodeDecoder, BoxedBarcodeData};
+
+mod ai;
+mod digital_link;
+
+/// The Group Separator used in place of FNC1 between elements.
+const GS: char = '\x1d';
+
+#[derive(Debug)]
+pub(crate) struct Gs1Decoder;
+
+#[async_trait]
+impl BarcodeDecoder for Gs1Decoder {
+    fn name(&self) -> &'static str {
+        "GS1"
+    }
+
+    fn settings(&self, _ui: &mut Ui) {}
+
+    fn symbologies(&self) -> Option<&'static [Symbology]> {
+        Some(&[
+            Symbology::Gs1128,
+            Symbology::Gs1DataMatrix,
+            Symbology::Gs1QrCode,
+            Symbology::Gs1DataBar,
+        ])
+    }
+
+    /// Without a symbology identifier, plain data like an EAN could be
+    /// mistaken for an element string, so require a leading FNC1 or
+    /// parentheses around the Application Identifiers.
+    fn accepts_unidentified(&self, input: &str) -> bool {
+        input.starts_with([GS, '('])
+    }
+
+    async fn decode(&self, input: &str) -> eyre::Result<BoxedBarcodeData> {
+        let elements = parse_element_string(input)?;
+
+        Ok(Box::new(Gs1Data::new(elements, None)))
+    }
+}
+
+/// GS1 Digital Link URLs, which contain Application Identifiers in their path
+/// and query.
+#[derive(Debug)]
+pub(crate) struct DigitalLinkDecoder;
+
+#[async_trait]
+impl BarcodeDecoder for DigitalLinkDecoder {
+    fn name(&self) -> &'static str {
+        "GS1 Digital Link"
+    }
+
+    fn settings(&self, _ui: &mut Ui) {}
+
+    async fn decode(&self, input: &str) -> eyre::Result<BoxedBarcodeData> {
+        let url = url::Url::parse(input.trim())?;
+
+        eyre::ensure!(
+            matches!(url.scheme(), "http" | "https"),
+            "must be http or https url"
+        );
+
+        let elements = digital_link::parse_digital_link(&url)?;
+
+        Ok(Box::new(Gs1Data::new(elements, Some(url))))
+    }
+}
+
+/// A single Application Identifier and its data.
+#[derive(Debug)]
+pub(super) struct Element {
+    pub(super) ai: String,
+    definition: &'static AiDefinition,
+    pub(super) value: String,
+    /// Why the data isn't valid for the Application Identifier.
+    error: Option<String>,
+}
+
+impl Element {
+    /// Create an element, checking that the data is valid for the
+    /// Application Identifier.
+    pub(super) fn new(ai: &str, value: &str) -> eyre::Result<Self> {
+        let definition =
+            ai::definition(ai).ok_or_else(|| eyre::eyre!("unknown application identifier {ai}"))?;
+
+        let valid_chars = match definition.content {
+            Content::Numeric => value.chars().all(|ch| ch.is_ascii_digit()),
+            Content::Alphanumeric => value.chars().all(ai::is_cset82),
+        };
+        eyre::ensure!(valid_chars, "invalid characters for ({ai})");
+
+        match definition.length {
+            Length::Fixed(len) => {
+                eyre::ensure!(value.len() == len, "({ai}) must be {len} characters")
+            }
+            Length::Variable(max) => eyre::ensure!(
+                !value.is_empty() && value.len() <= max,
+                "({ai}) must be 1 to {max} characters"
+            ),
+        }
+
+        let error = Self::validate(definition, value)
+            .err()
+            .map(|err| err.to_string());
+
+        Ok(Self {
+            ai: ai.to_string(),
+            definition,
+            value: value.to_string(),
+            error,
+        })
+    }
+
+    fn validate(definition: &AiDefinition, value: &str) -> eyre::Result<()> {
+        match definition.kind {
+            Kind::Text | Kind::Decimal => (),
+            Kind::CheckDigit => ai::validate_check_digit(value)?,
+            Kind::CheckDigitPrefix(len) => {
+                eyre::ensure!(value.len() >= len, "too short for check digit");
+                ai::validate_check_digit(&value[..len])?;
+            }
+            Kind::Date => {
+                ai::parse_date(value)?;
+            }
+            Kind::CurrencyDecimal => {
+                eyre::ensure!(
+                    value.len() > 3 && value[..3].bytes().all(|b| b.is_ascii_digit()),
+                    "missing currency code"
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    pub(super) fn title(&self) -> &'static str {
+        self.definition.title
+    }
+
+    /// The date, if this element contains a valid date.
+    fn date(&self) -> Option<time::Date> {
+        match self.definition.kind {
+            Kind::Date => ai::parse_date(&self.value).ok(),
+            _ => None,
+        }
+    }
+
+    /// If this is an expiration date that has passed.
+    pub(super) fn is_expired(&self) -> bool {
+        let today = time::OffsetDateTime::now_local().map(|now| now.date());
+
+        match (self.definition.is_expiry(), self.date(), today) {
+            (true, Some(date), Ok(today)) => today > date,
+            _ => false,
+        }
+    }
+
+    /// The data formatted for display.
+    pub(super) fn display_value(&self) -> String {
+        if self.error.is_some() {
+            return self.value.clone();
+        }
+
+        match self.definition.kind {
+            Kind::Date => self
+                .date()
+                .map(|date| date.to_string())
+                .unwrap_or_else(|| self.value.clone()),
+            Kind::Decimal => self.decimal(&self.value),
+            Kind::CurrencyDecimal => {
+                let (currency, amount) = self.value.split_at(3);
+                format!("{} ({currency})", self.decimal(amount))
+            }
+            _ => self.value.clone(),
+        }
+    }
+
+    fn decimal(&self, value: &str) -> String {
+        let places = self
+            .ai
+            .chars()
+            .last()
+            .and_then(|ch| ch.to_digit(10))
+            .unwrap_or_default() as usize;
+
+        let value = format!("{value:0>width$}", width = places + 1);
+        let (whole, fraction) = value.split_at(value.len() - places);
+        let whole = match whole.trim_start_matches('0') {
+            "" => "0",
+            whole => whole,
+        };
+
+        if fraction.is_empty() {
+            whole.to_string()
+        } else {
+            format!("{whole}.{fraction}")
+        }
+    }
+
+    pub(super) fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+}
+
+/// Parse a GS1 element string, either as transmitted with FNC1 separators or
+/// in the human readable format with Application Identifiers in parentheses.
+pub(super) fn parse_element_string(input: &str) -> eyre::Result<Vec<Element>> {
+    let input = input.trim_end_matches(['\r', '\n']);
+
+    let elements = if input.starts_with('(') {
+        parse_human_readable(input)?
+    } else {
+        parse_transmitted(input)?
+    };
+
+    eyre::ensure!(!elements.is_empty(), "no elements");
+
+    Ok(elements)
+}
+
+fn parse_human_readable(input: &str) -> eyre::Result<Vec<Element>> {
+    let mut elements = Vec::new();
+    let mut rest = input;
+
+    while let Some(data) = rest.strip_prefix('(') {
+        let (ai, data) = data
+            .split_once(')')
+            .ok_or_else(|| eyre::eyre!("unclosed application identifier"))?;
+
+        let end = data.find('(').unwrap_or(data.len());
+        elements.push(Element::new(ai, &data[..end])?);
+        rest = &data[end..];
+    }
+
+    eyre::ensure!(rest.is_empty(), "unexpected data after elements");
+
+    Ok(elements)
+}
+
+fn parse_transmitted(input: &str) -> eyre::Result<Vec<Element>> {
+    let mut elements = Vec::new();
+    let mut rest = input.trim_start_matches(GS);
+
+    while !rest.is_empty() {
+        let definition = ai::find_definition(rest)
+            .ok_or_else(|| eyre::eyre!("unknown application identifier"))?;
+        let (ai, data) = rest.split_at(definition.ai_len);
+
+        let end = match definition.length {
+            Length::Fixed(len) => {
+                eyre::ensure!(data.len() >= len, "({ai}) data was too short");
+                len
+            }
+            Length::Variable(_) => data.find(GS).unwrap_or(data.len()),
+        };
+        eyre::ensure!(data.is_char_boundary(end), "invalid data for ({ai})");
+
+        elements.push(Element::new(ai, &data[..end])?);
+
+        // Fixed length elements may still be followed by a separator.
+        rest = data[end..].strip_prefix(GS).unwrap_or(&data[end..]);
+    }
+
+    Ok(elements)
+}
+
+/// Render elements as a table of their Application Identifier, title, and
+/// data.
+pub(super) fn elements_grid(id: Uuid, elements: &[Element], ui: &mut Ui) {
+    Grid::new(id)
+        .num_columns(3)
+        .striped(true)
+        .spacing([20.0, 4.0])
+        .show(ui, |ui| {
+            for element in elements {
+                ui.label(RichText::new(format!("({})", element.ai)).monospace());
+                ui.strong(element.title());
+
+                let text = element.display_value();
+                if let Some(error) = element.error() {
+                    ui.colored_label(Color32::RED, format!("❌ {text}"))
+                        .on_hover_text(error);
+                } else if element.is_expired() {
+                    ui.colored_label(Color32::YELLOW, format!("⏰ {text}"))
+                        .on_hover_text("Expired");
+                } else {
+                    ui.label(text);
+                }
+
+                ui.end_row();
+            }
+        });
+}
+
+/// Build an object of each Application Identifier's data.
+pub(super) fn elements_json(elements: &[Element]) -> serde_json::Value {
+    elements
+        .iter()
+        .map(|element| (element.ai.clone(), element.value.clone().into()))
+        .collect::<serde_json::Map<_, _>>()
+        .into()
+}
+
+/// A short description of the most identifying element.
+pub(super) fn elements_summary(elements: &[Element]) -> String {
+    let element = ["01", "00", "8003", "8004", "414", "253"]
+        .iter()
+        .find_map(|ai| elements.iter().find(|element| element.ai == *ai))
+        .or_else(|| elements.first());
+
+    match element {
+        Some(element) => format!("{} {}", element.title(), element.value),
+        None => "Empty".to_string(),
+    }
+}
+
+#[derive(Debug)]
+struct Gs1Data {
+    id: Uuid,
+    elements: Vec<Element>,
+    /// The Digital Link the elements were read from.
+    link: Option<url::Url>,
+    raw_data: serde_json::Value,
+}
+
+impl Gs1Data {
+    fn new(elements: Vec<Element>, link: Option<url::Url>) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            raw_data: elements_json(&elements),
+            elements,
+            link,
+        }
+    }
+}
+
+impl BarcodeData for Gs1Data {
+    fn id(&self) -> Uuid {
+        self.id
+    }
+
+    fn summary(&self) -> String {
+        elements_summary(&self.elements)
+    }
+
+    fn render(&self, ui: &mut Ui) {
+        elements_grid(self.id, &self.elements, ui);
+
+        if let Some(link) = &self.link {
+            ui.horizontal(|ui| {
+                ui.label("Resolver");
+                if ui.link(link.as_str()).clicked() {
+                    if let Err(err) = open::that(link.as_str()) {
+                        tracing::error!("could not open link: {err}");
+                    }
+                }
+            });
+        }
+
+        CollapsingHeader::new("Raw Data")
+            .id_source(format!("{}-raw", self.id))
+            .show(ui, |ui| {
+                let theme = egui_extras::syntax_highlighting::CodeTheme::from_memory(ui.ctx());
+                egui_extras::syntax_highlighting::code_view_ui(
+                    ui,
+                    &theme,
+                    &serde_json::to_string_pretty(&self.raw_data)
+                        .expect("could not reserialize data"),
+                    "json",
+                );
+            });
+    }
+
+    fn raw_data(&self) -> Option<&serde_json::Value> {
+        Some(&self.raw_data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pairs(elements: &[Element]) -> Vec<(&str, &str)> {
+        elements
+            .iter()
+            .map(|element| (element.ai.as_str(), element.value.as_str()))
+            .collect()
+    }
+
+    const EXAMPLE: &[(&str, &str)] = &[("01", "09506000134352"), ("17", "201225"), ("10", "ABC")];
+
+    #[test]
+    fn parses_human_readable() {
+        let elements = parse_element_string("(01)09506000134352(17)201225(10)ABC").unwrap();
+
+        assert_eq!(pairs(&elements), EXAMPLE);
+        assert!(elements.iter().all(|element| element.error().is_none()));
+    }
+
+    #[test]
+    fn parses_transmitted() {
+        let elements = parse_transmitted("\x1d01095060001343521720122510ABC").unwrap();
+        assert_eq!(pairs(&elements), EXAMPLE);
+    }
+
+    #[test]
+    fn separates_variable_length_elements() {
+        let elements = parse_transmitted("10ABC\x1d0109506000134352\x1d17201225").unwrap();
+        assert_eq!(
+            pairs(&elements),
+            [("10", "ABC"), ("01", "09506000134352"), ("17", "201225")]
+        );
+
+        // Without a separator, a variable length element continues to the end.
+        let elements = parse_transmitted("10ABC17201225").unwrap();
+        assert_eq!(pairs(&elements), [("10", "ABC17201225")]);
+    }
+
+    #[test]
+    fn rejects_short_fixed_length_elements() {
+        assert!(parse_transmitted("010950600013").is_err());
+    }
+
+    #[test]
+    fn reports_invalid_check_digit() {
+        let elements = parse_transmitted("0109506000134353").unwrap();
+        assert!(elements[0].error().is_some());
+    }
+
+    #[test]
+    fn requires_unambiguous_input_without_symbology() {
+        assert!(Gs1Decoder.accepts_unidentified("\x1d0109506000134352"));
+        assert!(Gs1Decoder.accepts_unidentified("(01)09506000134352"));
+        assert!(!Gs1Decoder.accepts_unidentified("9780306406157"));
+        assert!(!Gs1Decoder.accepts_unidentified("10ABC"));
+    }
+}
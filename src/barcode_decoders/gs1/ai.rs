@@ -0,0 +1,538 @@
+/// The characters allowed in an Application Identifier's data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Content {
+    /// Digits only.
+    Numeric,
+    /// The GS1 AI encodable character set 82.
+    Alphanumeric,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Length {
+    Fixed(usize),
+    /// Up to a maximum length, ended by FNC1 unless it is the last element.
+    Variable(usize),
+}
+
+/// How an Application Identifier's data should be validated and displayed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Kind {
+    Text,
+    /// Ends with a GS1 mod 10 check digit.
+    CheckDigit,
+    /// Starts with this many digits ending with a GS1 mod 10 check digit,
+    /// followed by optional data.
+    CheckDigitPrefix(usize),
+    /// A date in the format YYMMDD, where a day of 00 means the last day of
+    /// the month.
+    Date,
+    /// A number with the count of decimal places given by the last digit of
+    /// the Application Identifier.
+    Decimal,
+    /// An ISO 4217 currency code followed by a decimal amount.
+    CurrencyDecimal,
+}
+
+#[derive(Debug)]
+pub(super) struct AiDefinition {
+    /// The Application Identifier, or its prefix when it is longer.
+    pub(super) ai: &'static str,
+    /// The full length of the Application Identifier.
+    pub(super) ai_len: usize,
+    pub(super) title: &'static str,
+    pub(super) content: Content,
+    pub(super) length: Length,
+    pub(super) kind: Kind,
+}
+
+impl AiDefinition {
+    /// If a date in this Application Identifier means the item expires.
+    pub(super) fn is_expiry(&self) -> bool {
+        matches!(self.ai, "15" | "16" | "17")
+    }
+}
+
+const fn def(
+    ai: &'static str,
+    title: &'static str,
+    content: Content,
+    length: Length,
+    kind: Kind,
+) -> AiDefinition {
+    AiDefinition {
+        ai,
+        ai_len: ai.len(),
+        title,
+        content,
+        length,
+        kind,
+    }
+}
+
+/// An Application Identifier with fixed length numeric data.
+const fn n(ai: &'static str, title: &'static str, len: usize) -> AiDefinition {
+    def(ai, title, Content::Numeric, Length::Fixed(len), Kind::Text)
+}
+
+/// An Application Identifier with variable length numeric data.
+const fn nv(ai: &'static str, title: &'static str, max: usize) -> AiDefinition {
+    def(
+        ai,
+        title,
+        Content::Numeric,
+        Length::Variable(max),
+        Kind::Text,
+    )
+}
+
+/// An Application Identifier with variable length alphanumeric data.
+const fn x(ai: &'static str, title: &'static str, max: usize) -> AiDefinition {
+    def(
+        ai,
+        title,
+        Content::Alphanumeric,
+        Length::Variable(max),
+        Kind::Text,
+    )
+}
+
+/// An Application Identifier ending in a check digit.
+const fn check(ai: &'static str, title: &'static str, len: usize) -> AiDefinition {
+    def(
+        ai,
+        title,
+        Content::Numeric,
+        Length::Fixed(len),
+        Kind::CheckDigit,
+    )
+}
+
+/// An Application Identifier containing a date.
+const fn date(ai: &'static str, title: &'static str) -> AiDefinition {
+    def(ai, title, Content::Numeric, Length::Fixed(6), Kind::Date)
+}
+
+/// A four digit Application Identifier where the last digit is the number of
+/// decimal places.
+const fn decimal(prefix: &'static str, title: &'static str, length: Length) -> AiDefinition {
+    AiDefinition {
+        ai_len: 4,
+        ..def(prefix, title, Content::Numeric, length, Kind::Decimal)
+    }
+}
+
+/// A measurement with six digits and a decimal position.
+const fn measure(prefix: &'static str, title: &'static str) -> AiDefinition {
+    decimal(prefix, title, Length::Fixed(6))
+}
+
+/// A four digit Application Identifier where the last digit is a sequence
+/// number.
+const fn series(
+    prefix: &'static str,
+    title: &'static str,
+    content: Content,
+    max: usize,
+) -> AiDefinition {
+    AiDefinition {
+        ai_len: 4,
+        ..def(prefix, title, content, Length::Variable(max), Kind::Text)
+    }
+}
+
+/// Application Identifiers from the GS1 General Specifications.
+///
+/// Entries are checked in order, so an exact Application Identifier must
+/// come before a prefix that would also match it.
+static AI_TABLE: &[AiDefinition] = &[
+    check("00", "SSCC", 18),
+    check("01", "GTIN", 14),
+    check("02", "CONTENT", 14),
+    check("03", "MTO GTIN", 14),
+    x("10", "BATCH/LOT", 20),
+    date("11", "PROD DATE"),
+    date("12", "DUE DATE"),
+    date("13", "PACK DATE"),
+    date("15", "BEST BEFORE or BEST BY"),
+    date("16", "SELL BY"),
+    date("17", "USE BY or EXPIRY"),
+    n("20", "VARIANT", 2),
+    x("21", "SERIAL", 20),
+    x("22", "CPV", 20),
+    x("235", "TPX", 28),
+    x("240", "ADDITIONAL ID", 30),
+    x("241", "CUST. PART No.", 30),
+    nv("242", "MTO VARIANT", 6),
+    x("243", "PCN", 20),
+    x("250", "SECONDARY SERIAL", 30),
+    x("251", "REF. TO SOURCE", 30),
+    def(
+        "253",
+        "GDTI",
+        Content::Alphanumeric,
+        Length::Variable(30),
+        Kind::CheckDigitPrefix(13),
+    ),
+    x("254", "GLN EXTENSION COMPONENT", 20),
+    def(
+        "255",
+        "GCN",
+        Content::Numeric,
+        Length::Variable(25),
+        Kind::CheckDigitPrefix(13),
+    ),
+    nv("30", "VAR. COUNT", 8),
+    measure("310", "NET WEIGHT (kg)"),
+    measure("311", "LENGTH (m)"),
+    measure("312", "WIDTH (m)"),
+    measure("313", "HEIGHT (m)"),
+    measure("314", "AREA (m²)"),
+    measure("315", "NET VOLUME (l)"),
+    measure("316", "NET VOLUME (m³)"),
+    measure("320", "NET WEIGHT (lb)"),
+    measure("321", "LENGTH (in)"),
+    measure("322", "LENGTH (ft)"),
+    measure("323", "LENGTH (yd)"),
+    measure("324", "WIDTH (in)"),
+    measure("325", "WIDTH (ft)"),
+    measure("326", "WIDTH (yd)"),
+    measure("327", "HEIGHT (in)"),
+    measure("328", "HEIGHT (ft)"),
+    measure("329", "HEIGHT (yd)"),
+    measure("330", "GROSS WEIGHT (kg)"),
+    measure("331", "LENGTH (m), log"),
+    measure("332", "WIDTH (m), log"),
+    measure("333", "HEIGHT (m), log"),
+    measure("334", "AREA (m²), log"),
+    measure("335", "VOLUME (l), log"),
+    measure("336", "VOLUME (m³), log"),
+    measure("337", "KG PER m²"),
+    measure("340", "GROSS WEIGHT (lb)"),
+    measure("341", "LENGTH (in), log"),
+    measure("342", "LENGTH (ft), log"),
+    measure("343", "LENGTH (yd), log"),
+    measure("344", "WIDTH (in), log"),
+    measure("345", "WIDTH (ft), log"),
+    measure("346", "WIDTH (yd), log"),
+    measure("347", "HEIGHT (in), log"),
+    measure("348", "HEIGHT (ft), log"),
+    measure("349", "HEIGHT (yd), log"),
+    measure("350", "AREA (in²)"),
+    measure("351", "AREA (ft²)"),
+    measure("352", "AREA (yd²)"),
+    measure("353", "AREA (in²), log"),
+    measure("354", "AREA (ft²), log"),
+    measure("355", "AREA (yd²), log"),
+    measure("356", "NET WEIGHT (troy oz)"),
+    measure("357", "NET VOLUME (oz)"),
+    measure("360", "NET VOLUME (qt)"),
+    measure("361", "NET VOLUME (gal.)"),
+    measure("362", "VOLUME (qt), log"),
+    measure("363", "VOLUME (gal.), log"),
+    measure("364", "VOLUME (in³)"),
+    measure("365", "VOLUME (ft³)"),
+    measure("366", "VOLUME (yd³)"),
+    measure("367", "VOLUME (in³), log"),
+    measure("368", "VOLUME (ft³), log"),
+    measure("369", "VOLUME (yd³), log"),
+    nv("37", "COUNT", 8),
+    decimal("390", "AMOUNT", Length::Variable(15)),
+    AiDefinition {
+        kind: Kind::CurrencyDecimal,
+        ..decimal("391", "AMOUNT", Length::Variable(18))
+    },
+    decimal("392", "PRICE", Length::Variable(15)),
+    AiDefinition {
+        kind: Kind::CurrencyDecimal,
+        ..decimal("393", "PRICE", Length::Variable(18))
+    },
+    decimal("394", "PRCNT OFF", Length::Fixed(4)),
+    decimal("395", "PRICE/UoM", Length::Fixed(6)),
+    x("400", "ORDER NUMBER", 30),
+    x("401", "GINC", 30),
+    check("402", "GSIN", 17),
+    x("403", "ROUTE", 30),
+    check("410", "SHIP TO LOC", 13),
+    check("411", "BILL TO", 13),
+    check("412", "PURCHASE FROM", 13),
+    check("413", "SHIP FOR LOC", 13),
+    check("414", "LOC No.", 13),
+    check("415", "PAY TO", 13),
+    check("416", "PROD/SERV LOC", 13),
+    check("417", "PARTY", 13),
+    x("420", "SHIP TO POST", 20),
+    x("421", "SHIP TO POST", 12),
+    n("422", "ORIGIN", 3),
+    nv("423", "COUNTRY - INITIAL PROCESS.", 15),
+    n("424", "COUNTRY - PROCESS.", 3),
+    nv("425", "COUNTRY - DISASSEMBLY", 15),
+    n("426", "COUNTRY - FULL PROCESS", 3),
+    x("427", "ORIGIN SUBDIVISION", 3),
+    x("4300", "SHIP TO COMP", 35),
+    x("4301", "SHIP TO NAME", 35),
+    x("4302", "SHIP TO ADD1", 70),
+    x("4303", "SHIP TO ADD2", 70),
+    x("4304", "SHIP TO SUB", 70),
+    x("4305", "SHIP TO LOC", 70),
+    x("4306", "SHIP TO REG", 70),
+    def(
+        "4307",
+        "SHIP TO COUNTRY",
+        Content::Alphanumeric,
+        Length::Fixed(2),
+        Kind::Text,
+    ),
+    x("4308", "SHIP TO PHONE", 30),
+    n("4309", "SHIP TO GEO", 20),
+    x("4310", "RTN TO COMP", 35),
+    x("4311", "RTN TO NAME", 35),
+    x("4312", "RTN TO ADD1", 70),
+    x("4313", "RTN TO ADD2", 70),
+    x("4314", "RTN TO SUB", 70),
+    x("4315", "RTN TO LOC", 70),
+    x("4316", "RTN TO REG", 70),
+    def(
+        "4317",
+        "RTN TO COUNTRY",
+        Content::Alphanumeric,
+        Length::Fixed(2),
+        Kind::Text,
+    ),
+    x("4318", "RTN TO POST", 20),
+    x("4319", "RTN TO PHONE", 30),
+    x("4320", "SRV DESCRIPTION", 35),
+    n("4321", "DANGEROUS GOODS", 1),
+    n("4322", "AUTH LEAVE", 1),
+    n("4323", "SIG REQUIRED", 1),
+    n("4324", "NBEF DEL DT", 10),
+    n("4325", "NAFT DEL DT", 10),
+    date("4326", "REL DATE"),
+    x("4330", "MAX TEMP F", 7),
+    x("4331", "MAX TEMP C", 7),
+    x("4332", "MIN TEMP F", 7),
+    x("4333", "MIN TEMP C", 7),
+    n("7001", "NSN", 13),
+    x("7002", "MEAT CUT", 30),
+    n("7003", "EXPIRY TIME", 10),
+    nv("7004", "ACTIVE POTENCY", 4),
+    x("7005", "CATCH AREA", 12),
+    date("7006", "FIRST FREEZE DATE"),
+    nv("7007", "HARVEST DATE", 12),
+    x("7008", "AQUATIC SPECIES", 3),
+    x("7009", "FISHING GEAR TYPE", 10),
+    x("7010", "PROD METHOD", 2),
+    x("7011", "TEST BY DATE", 10),
+    x("7020", "REFURB LOT", 20),
+    x("7021", "FUNC STAT", 20),
+    x("7022", "REV STAT", 20),
+    x("7023", "GIAI - ASSEMBLY", 30),
+    series("703", "PROCESSOR #", Content::Alphanumeric, 30),
+    x("7040", "UIC+EXT", 4),
+    x("7041", "UFRGT UNIT TYPE", 4),
+    x("710", "NHRN PZN", 20),
+    x("711", "NHRN CIP", 20),
+    x("712", "NHRN CN", 20),
+    x("713", "NHRN DRN", 20),
+    x("714", "NHRN AIM", 20),
+    x("715", "NHRN NDC", 20),
+    x("716", "NHRN AIC", 20),
+    series("723", "CERT #", Content::Alphanumeric, 30),
+    x("7240", "PROTOCOL", 20),
+    n("7241", "AIDC MEDIA TYPE", 2),
+    x("7242", "VCN", 25),
+    n("7250", "DOB", 8),
+    n("7251", "DOB TIME", 12),
+    n("7252", "BIO SEX", 1),
+    x("7253", "FAMILY NAME", 40),
+    x("7254", "GIVEN NAME", 40),
+    x("7255", "SUFFIX", 10),
+    x("7256", "FULL NAME", 90),
+    x("7257", "PERSON ADDR", 70),
+    x("7258", "BIRTH SEQUENCE", 3),
+    x("7259", "BABY", 40),
+    n("8001", "DIMENSIONS", 14),
+    x("8002", "CMT No.", 20),
+    def(
+        "8003",
+        "GRAI",
+        Content::Alphanumeric,
+        Length::Variable(30),
+        Kind::CheckDigitPrefix(14),
+    ),
+    x("8004", "GIAI", 30),
+    n("8005", "PRICE PER UNIT", 6),
+    def(
+        "8006",
+        "ITIP",
+        Content::Numeric,
+        Length::Fixed(18),
+        Kind::CheckDigitPrefix(14),
+    ),
+    x("8007", "IBAN", 34),
+    nv("8008", "PROD TIME", 12),
+    x("8009", "OPTSEN", 50),
+    x("8010", "CPID", 30),
+    nv("8011", "CPID SERIAL", 12),
+    x("8012", "VERSION", 20),
+    x("8013", "GMN", 25),
+    check("8017", "GSRN - PROVIDER", 18),
+    check("8018", "GSRN - RECIPIENT", 18),
+    nv("8019", "SRIN", 10),
+    x("8020", "REF No.", 25),
+    def(
+        "8026",
+        "ITIP CONTENT",
+        Content::Numeric,
+        Length::Fixed(18),
+        Kind::CheckDigitPrefix(14),
+    ),
+    x("8030", "DIGSIG", 90),
+    x("8110", "COUPON", 70),
+    n("8111", "POINTS", 4),
+    x("8112", "COUPON", 70),
+    x("8200", "PRODUCT URL", 70),
+    x("90", "INTERNAL", 30),
+    AiDefinition {
+        ai_len: 2,
+        ..x("9", "INTERNAL", 90)
+    },
+];
+
+/// Find the definition for the Application Identifier at the start of the
+/// data.
+pub(super) fn find_definition(data: &str) -> Option<&'static AiDefinition> {
+    AI_TABLE.iter().find(|definition| {
+        data.starts_with(definition.ai)
+            && data.len() >= definition.ai_len
+            && data[..definition.ai_len]
+                .bytes()
+                .all(|b| b.is_ascii_digit())
+    })
+}
+
+/// Find the definition for an exact Application Identifier.
+pub(super) fn definition(ai: &str) -> Option<&'static AiDefinition> {
+    find_definition(ai).filter(|definition| definition.ai_len == ai.len())
+}
+
+/// If a character is in the GS1 AI encodable character set 82.
+pub(super) fn is_cset82(ch: char) -> bool {
+    ch.is_ascii_alphanumeric() || "!\"%&'()*+,-./:;<=>?_".contains(ch)
+}
+
+/// Calculate the GS1 mod 10 check digit for digits excluding the check
+/// digit.
+pub(super) fn check_digit(digits: &str) -> Option<u32> {
+    let sum = digits
+        .chars()
+        .rev()
+        .enumerate()
+        .try_fold(0, |sum, (index, ch)| {
+            let digit = ch.to_digit(10)?;
+            let weight = if index % 2 == 0 { 3 } else { 1 };
+            Some(sum + digit * weight)
+        })?;
+
+    Some((10 - sum % 10) % 10)
+}
+
+/// Validate that digits end with a correct check digit.
+pub(super) fn validate_check_digit(digits: &str) -> eyre::Result<()> {
+    let (data, check) = digits.split_at(digits.len().saturating_sub(1));
+
+    let expected =
+        check_digit(data).ok_or_else(|| eyre::eyre!("check digit data must be numeric"))?;
+    eyre::ensure!(
+        check.parse::<u32>().ok() == Some(expected),
+        "invalid check digit, expected {expected}"
+    );
+
+    Ok(())
+}
+
+/// Parse a GS1 YYMMDD date.
+///
+/// The century is picked to be within 49 years in the past or 50 years in the
+/// future of the current year.
+pub(super) fn parse_date(data: &str) -> eyre::Result<time::Date> {
+    eyre::ensure!(
+        data.len() == 6 && data.bytes().all(|b| b.is_ascii_digit()),
+        "date must be six digits"
+    );
+
+    let yy: i32 = data[0..2].parse()?;
+    let month: u8 = data[2..4].parse()?;
+    let day: u8 = data[4..6].parse()?;
+
+    let current_year = time::OffsetDateTime::now_utc().year();
+    let century = current_year / 100 * 100;
+    let year = match yy - current_year % 100 {
+        51..=99 => century - 100 + yy,
+        -99..=-50 => century + 100 + yy,
+        _ => century + yy,
+    };
+
+    let month = time::Month::try_from(month).map_err(|_| eyre::eyre!("invalid month"))?;
+
+    // A day of 00 means the last day of the month.
+    if day == 0 {
+        return (28..=31)
+            .rev()
+            .find_map(|day| time::Date::from_calendar_date(year, month, day).ok())
+            .ok_or_else(|| eyre::eyre!("invalid date"));
+    }
+
+    time::Date::from_calendar_date(year, month, day)
+        .map_err(|err| eyre::eyre!("invalid date: {err}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use time::{Date, Month};
+
+    use super::*;
+
+    #[test]
+    fn calculates_check_digit() {
+        assert_eq!(check_digit("0950600013435"), Some(2));
+        assert_eq!(check_digit("37610425002123456"), Some(9));
+        assert_eq!(check_digit("095060001343A"), None);
+
+        assert!(validate_check_digit("09506000134352").is_ok());
+        assert!(validate_check_digit("09506000134353").is_err());
+    }
+
+    #[test]
+    fn parses_date() {
+        assert_eq!(
+            parse_date("201225").unwrap(),
+            Date::from_calendar_date(2020, Month::December, 25).unwrap()
+        );
+
+        assert!(parse_date("201325").is_err());
+        assert!(parse_date("200230").is_err());
+        assert!(parse_date("2012").is_err());
+    }
+
+    #[test]
+    fn parses_date_with_day_zero_as_last_day() {
+        assert_eq!(
+            parse_date("240200").unwrap(),
+            Date::from_calendar_date(2024, Month::February, 29).unwrap()
+        );
+        assert_eq!(
+            parse_date("230200").unwrap(),
+            Date::from_calendar_date(2023, Month::February, 28).unwrap()
+        );
+    }
+
+    #[test]
+    fn picks_date_century() {
+        let current_year = time::OffsetDateTime::now_utc().year();
+        let year = |offset: i32| parse_date(&format!("{:02}0101", (current_year + offset) % 100));
+
+        assert_eq!(year(0).unwrap().year(), current_year);
+        assert_eq!(year(50).unwrap().year(), current_year + 50);
+        assert_eq!(year(51).unwrap().year(), current_year - 49);
+    }
+}
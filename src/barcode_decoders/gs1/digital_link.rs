@@ -0,0 +1,154 @@
+use percent_encoding::percent_decode_str;
+
+use super::Element;
+
+/// Application Identifiers that may identify the item in a Digital Link path.
+const PRIMARY_KEYS: &[&str] = &[
+    "01", "00", "253", "255", "401", "402", "414", "417", "8003", "8004", "8006", "8010", "8013",
+    "8017", "8018",
+];
+
+/// The short names that may be used in place of numeric Application
+/// Identifiers.
+static SHORT_NAMES: phf::Map<&'static str, &'static str> = phf::phf_map! {
+    "gtin" => "01",
+    "sscc" => "00",
+    "cpv" => "22",
+    "lot" => "10",
+    "ser" => "21",
+    "exp" => "17",
+    "gdti" => "253",
+    "glnx" => "254",
+    "gcn" => "255",
+    "ginc" => "401",
+    "gsin" => "402",
+    "gln" => "414",
+    "party" => "417",
+    "grai" => "8003",
+    "giai" => "8004",
+    "itip" => "8006",
+    "cpid" => "8010",
+    "gmn" => "8013",
+    "gsrnp" => "8017",
+    "gsrn" => "8018",
+};
+
+/// Get the numeric Application Identifier for a path segment or query key.
+fn resolve_ai(key: &str) -> Option<&str> {
+    if let Some(ai) = SHORT_NAMES.get(key) {
+        return Some(ai);
+    }
+
+    super::ai::definition(key).map(|_definition| key)
+}
+
+/// Create an element from Digital Link data, expanding shortened GTINs.
+fn element(ai: &str, value: &str) -> eyre::Result<Element> {
+    if ai == "01" && matches!(value.len(), 8 | 12 | 13) {
+        Element::new(ai, &format!("{value:0>14}"))
+    } else {
+        Element::new(ai, value)
+    }
+}
+
+/// Decompose a GS1 Digital Link URL into its elements.
+///
+/// The path may start with any number of segments before the primary key,
+/// which is followed by pairs of key qualifiers. Application Identifiers in the
+/// query are also included, while other parameters are ignored.
+pub(super) fn parse_digital_link(url: &url::Url) -> eyre::Result<Vec<Element>> {
+    let segments = url
+        .path_segments()
+        .ok_or_else(|| eyre::eyre!("url has no path"))?
+        .filter(|segment| !segment.is_empty())
+        .map(|segment| percent_decode_str(segment).decode_utf8())
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let start = (0..segments.len())
+        .find(|index| {
+            (segments.len() - index) % 2 == 0
+                && resolve_ai(&segments[*index]).is_some_and(|ai| PRIMARY_KEYS.contains(&ai))
+        })
+        .ok_or_else(|| eyre::eyre!("url did not contain a primary key"))?;
+
+    let mut elements = Vec::new();
+
+    for pair in segments[start..].chunks_exact(2) {
+        let ai = resolve_ai(&pair[0])
+            .ok_or_else(|| eyre::eyre!("unknown application identifier {}", pair[0]))?;
+        elements.push(element(ai, &pair[1])?);
+    }
+
+    for (key, value) in url.query_pairs() {
+        let Some(ai) = resolve_ai(&key) else {
+            continue;
+        };
+
+        if elements.iter().any(|element| element.ai == ai) {
+            continue;
+        }
+
+        elements.push(element(ai, &value)?);
+    }
+
+    Ok(elements)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(url: &str) -> eyre::Result<Vec<(String, String)>> {
+        let elements = parse_digital_link(&url::Url::parse(url)?)?;
+
+        Ok(elements
+            .into_iter()
+            .map(|element| (element.ai, element.value))
+            .collect())
+    }
+
+    fn pairs(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs
+            .iter()
+            .map(|(ai, value)| (ai.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn parses_path_and_query() {
+        assert_eq!(
+            parse("https://id.gs1.org/01/09506000134352/10/ABC?17=201225").unwrap(),
+            pairs(&[("01", "09506000134352"), ("10", "ABC"), ("17", "201225")])
+        );
+    }
+
+    #[test]
+    fn skips_leading_path_segments() {
+        assert_eq!(
+            parse("https://example.com/products/view/01/09506000134352").unwrap(),
+            pairs(&[("01", "09506000134352")])
+        );
+    }
+
+    #[test]
+    fn expands_short_names_and_gtins() {
+        assert_eq!(
+            parse("https://example.com/gtin/9506000134352/lot/ABC%2F1?exp=201225").unwrap(),
+            pairs(&[("01", "09506000134352"), ("10", "ABC/1"), ("17", "201225")])
+        );
+    }
+
+    #[test]
+    fn ignores_other_query_parameters() {
+        assert_eq!(
+            parse("https://id.gs1.org/01/09506000134352?linkType=gs1:pip&3103=000189").unwrap(),
+            pairs(&[("01", "09506000134352"), ("3103", "000189")])
+        );
+    }
+
+    #[test]
+    fn requires_primary_key() {
+        assert!(parse("https://example.com/10/ABC").is_err());
+        assert!(parse("https://example.com/about").is_err());
+    }
+}
@@ -1,6 +1,6 @@
 use std::{
     borrow::Cow,
-    collections::HashMap,
+    collections::{BTreeMap, HashMap},
     fmt::Debug,
     io::Read,
     sync::{Arc, Mutex},
@@ -22,7 +22,7 @@ use sqlx::{SqliteExecutor, SqlitePool};
 use time::macros::format_description;
 use uuid::Uuid;
 
-use crate::ui::state_worker::StateWorker;
+use crate::{barcode_scanner::Symbology, ui::state_worker::StateWorker};
 
 use super::{BarcodeData, BarcodeDecoder, BoxedBarcodeData};
 
@@ -51,9 +51,64 @@ struct UiState {
     vci_issuer_loaded: usize,
     vci_issuer_total: usize,
     vci_issuers: Vec<VciIssuerMeta>,
+    chunks: Option<QrChunks>,
+}
+
+/// Chunks of a JWS that was split across multiple QR codes.
+#[derive(Debug)]
+struct QrChunks {
+    total: usize,
+    payloads: BTreeMap<usize, String>,
+}
+
+/// The data from a single QR code, which may be one chunk of a JWS.
+struct QrChunk {
+    /// The one-based position of this chunk.
+    index: usize,
+    total: usize,
+    payload: String,
 }
 
 impl UiState {
+    /// Add a chunk, returning the complete JWS if every chunk has been
+    /// collected.
+    fn add_chunk(&mut self, chunk: QrChunk) -> Option<String> {
+        if chunk.total == 1 {
+            return Some(chunk.payload);
+        }
+
+        // A different payload for a chunk we already have means a new card
+        // was started before the last one was finished.
+        let conflicting = self
+            .chunks
+            .as_ref()
+            .and_then(|chunks| chunks.payloads.get(&chunk.index))
+            .is_some_and(|payload| *payload != chunk.payload);
+
+        if conflicting {
+            tracing::debug!(index = chunk.index, "discarding incomplete chunks");
+            self.chunks = None;
+        }
+
+        let chunks = match &mut self.chunks {
+            Some(chunks) if chunks.total == chunk.total => chunks,
+            chunks => chunks.insert(QrChunks {
+                total: chunk.total,
+                payloads: BTreeMap::new(),
+            }),
+        };
+
+        chunks.payloads.insert(chunk.index, chunk.payload);
+
+        if chunks.payloads.len() < chunks.total {
+            return None;
+        }
+
+        self.chunks
+            .take()
+            .map(|chunks| chunks.payloads.into_values().collect())
+    }
+
     fn add_issuer(&mut self, meta: VciIssuerMeta) {
         self.vci_issuers.push(meta);
         self.vci_issuers
@@ -69,6 +124,7 @@ impl Debug for UiState {
             .field("vci_issuers_loading", &self.vci_issuers_loading)
             .field("vci_issuer_loaded", &self.vci_issuer_loaded)
             .field("vci_issuer_total", &self.vci_issuer_total)
+            .field("chunks", &self.chunks)
             .finish_non_exhaustive()
     }
 }
@@ -460,11 +516,23 @@ impl SmartHealthCardDecoder {
         .map_err(Into::into)
     }
 
-    fn decode_qr_data(input: &str) -> eyre::Result<String> {
+    fn decode_qr_data(input: &str) -> eyre::Result<QrChunk> {
         let data = input
             .trim()
             .strip_prefix("shc:/")
             .ok_or_else(|| eyre::eyre!("missing SHC prefix"))?;
+
+        // Chunked data is prefixed with the chunk's position and the total
+        // number of chunks, like `shc:/1/3/...`.
+        let (index, total, data) = match data.split('/').collect::<Vec<_>>()[..] {
+            [data] => (1, 1, data),
+            [index, total, data] => (index.parse()?, total.parse()?, data),
+            _ => eyre::bail!("invalid SHC chunk format"),
+        };
+        eyre::ensure!(
+            total > 0 && (1..=total).contains(&index),
+            "invalid SHC chunk position"
+        );
         eyre::ensure!(data.len() % 2 == 0, "data length must be even");
 
         let mut payload = String::with_capacity(data.len() / 2);
@@ -476,7 +544,11 @@ impl SmartHealthCardDecoder {
             payload.push(ch);
         }
 
-        Ok(payload)
+        Ok(QrChunk {
+            index,
+            total,
+            payload,
+        })
     }
 
     fn decompress_data<'a>(payload_parts: &[&'a str]) -> eyre::Result<Cow<'a, str>> {
@@ -541,6 +613,24 @@ impl BarcodeDecoder for SmartHealthCardDecoder {
             ui_state.showing_vci_issuers = true;
         }
 
+        if let Some(chunks) = &ui_state.chunks {
+            ui.separator();
+
+            ui.label(format!(
+                "Scanned {}/{} QR Code Chunks",
+                chunks.payloads.len(),
+                chunks.total
+            ));
+            ui.add(
+                ProgressBar::new(chunks.payloads.len() as f32 / chunks.total as f32)
+                    .show_percentage(),
+            );
+
+            if ui.button("Discard Chunks").clicked() {
+                ui_state.chunks = None;
+            }
+        }
+
         ui.separator();
 
         ui.add_enabled_ui(!ui_state.showing_cvx_codes, |ui| {
@@ -650,9 +740,40 @@ impl BarcodeDecoder for SmartHealthCardDecoder {
         ui_state.showing_vci_issuers = showing;
     }
 
+    fn symbologies(&self) -> Option<&'static [Symbology]> {
+        Some(&[Symbology::QrCode])
+    }
+
     async fn decode(&self, input: &str) -> eyre::Result<BoxedBarcodeData> {
-        let qr_data = Self::decode_qr_data(input)?;
-        tracing::trace!(input, "got payload data");
+        let chunk = Self::decode_qr_data(input)?;
+        tracing::trace!(
+            input,
+            index = chunk.index,
+            total = chunk.total,
+            "got payload data"
+        );
+
+        let (index, total) = (chunk.index, chunk.total);
+        let (qr_data, received) = {
+            let mut ui_state = self.ui_state.lock().unwrap();
+            let qr_data = ui_state.add_chunk(chunk);
+            let received = ui_state
+                .chunks
+                .as_ref()
+                .map(|chunks| chunks.payloads.len())
+                .unwrap_or_default();
+
+            (qr_data, received)
+        };
+
+        let Some(qr_data) = qr_data else {
+            return Ok(Box::new(SmartHealthCardChunk {
+                id: Uuid::new_v4(),
+                index,
+                total,
+                received,
+            }));
+        };
 
         let header = jsonwebtoken::decode_header(&qr_data)?;
         tracing::trace!(?header, "got jwt header");
@@ -960,3 +1081,141 @@ impl BarcodeData for SmartHealthCardData {
         Some(&self.raw_data)
     }
 }
+
+/// A chunk of a SMART Health Card that was split across multiple QR codes.
+#[derive(Debug)]
+struct SmartHealthCardChunk {
+    id: Uuid,
+    index: usize,
+    total: usize,
+    received: usize,
+}
+
+impl BarcodeData for SmartHealthCardChunk {
+    fn id(&self) -> Uuid {
+        self.id
+    }
+
+    fn summary(&self) -> String {
+        format!("Chunk {} of {}", self.index, self.total)
+    }
+
+    fn render(&self, ui: &mut Ui) {
+        ui.label(format!(
+            "Scanned {} of {} chunks, scan the rest to decode the card.",
+            self.received, self.total
+        ));
+    }
+
+    fn raw_data(&self) -> Option<&serde_json::Value> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chunk(index: usize, total: usize, payload: &str) -> QrChunk {
+        QrChunk {
+            index,
+            total,
+            payload: payload.to_string(),
+        }
+    }
+
+    #[test]
+    fn decodes_single_qr_code() {
+        let chunk = SmartHealthCardDecoder::decode_qr_data("shc:/5676290952432060").unwrap();
+
+        assert_eq!((chunk.index, chunk.total), (1, 1));
+        assert_eq!(chunk.payload, "eyJ6aXAi");
+    }
+
+    #[test]
+    fn decodes_chunked_qr_code() {
+        let chunk = SmartHealthCardDecoder::decode_qr_data("shc:/2/3/5676290952432060").unwrap();
+
+        assert_eq!((chunk.index, chunk.total), (2, 3));
+        assert_eq!(chunk.payload, "eyJ6aXAi");
+    }
+
+    #[test]
+    fn rejects_invalid_chunk_positions() {
+        for input in [
+            "shc:/0/3/5676",
+            "shc:/4/3/5676",
+            "shc:/1/0/5676",
+            "shc:/a/3/5676",
+            "shc:/1/2/3/5676",
+        ] {
+            assert!(
+                SmartHealthCardDecoder::decode_qr_data(input).is_err(),
+                "{input}"
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_qr_data() {
+        assert!(SmartHealthCardDecoder::decode_qr_data("5676290952432060").is_err());
+        assert!(SmartHealthCardDecoder::decode_qr_data("shc:/567").is_err());
+        assert!(SmartHealthCardDecoder::decode_qr_data("shc:/56ab").is_err());
+    }
+
+    #[test]
+    fn passes_through_single_chunks() {
+        let mut ui_state = UiState::default();
+
+        assert_eq!(
+            ui_state.add_chunk(chunk(1, 1, "a.b.c")).as_deref(),
+            Some("a.b.c")
+        );
+        assert!(ui_state.chunks.is_none());
+    }
+
+    #[test]
+    fn completes_out_of_order() {
+        let mut ui_state = UiState::default();
+
+        assert_eq!(ui_state.add_chunk(chunk(3, 3, "c")), None);
+        assert_eq!(ui_state.add_chunk(chunk(1, 3, "a.")), None);
+        // Scanning the same code twice doesn't change anything.
+        assert_eq!(ui_state.add_chunk(chunk(1, 3, "a.")), None);
+        assert_eq!(
+            ui_state.add_chunk(chunk(2, 3, "b.")).as_deref(),
+            Some("a.b.c")
+        );
+        assert!(ui_state.chunks.is_none());
+    }
+
+    #[test]
+    fn restarts_when_total_changes() {
+        let mut ui_state = UiState::default();
+
+        assert_eq!(ui_state.add_chunk(chunk(1, 3, "x.")), None);
+        assert_eq!(ui_state.add_chunk(chunk(2, 2, "c")), None);
+        assert_eq!(
+            ui_state.add_chunk(chunk(1, 2, "a.b.")).as_deref(),
+            Some("a.b.c")
+        );
+    }
+
+    #[test]
+    fn restarts_when_chunk_conflicts() {
+        let mut ui_state = UiState::default();
+
+        assert_eq!(ui_state.add_chunk(chunk(1, 3, "x.")), None);
+        assert_eq!(ui_state.add_chunk(chunk(2, 3, "y.")), None);
+
+        // A different first chunk starts a new card, so the old second chunk
+        // must not be used.
+        assert_eq!(ui_state.add_chunk(chunk(1, 3, "a.")), None);
+        assert_eq!(ui_state.chunks.as_ref().unwrap().payloads.len(), 1);
+        assert_eq!(ui_state.add_chunk(chunk(3, 3, "c")), None);
+        assert_eq!(
+            ui_state.add_chunk(chunk(2, 3, "b.")).as_deref(),
+            Some("a.b.c")
+        );
+    }
+}
@@ -0,0 +1,270 @@
+use async_trait::async_trait;
+use eframe::egui::{Grid, Id, RichText, Ui};
+use uuid::Uuid;
+
+use crate::barcode_scanner::Symbology;
+
+use super::{
+    escape::{split_unescaped, unescape},
+    BarcodeData, BarcodeDecoder, BoxedBarcodeData,
+};
+
+#[derive(Debug)]
+pub(crate) struct WifiDecoder;
+
+#[async_trait]
+impl BarcodeDecoder for WifiDecoder {
+    fn name(&self) -> &'static str {
+        "Wi-Fi Network"
+    }
+
+    fn settings(&self, _ui: &mut Ui) {}
+
+    fn symbologies(&self) -> Option<&'static [Symbology]> {
+        Some(&[Symbology::QrCode])
+    }
+
+    async fn decode(&self, input: &str) -> eyre::Result<BoxedBarcodeData> {
+        let network = WifiNetwork::parse(input)?;
+
+        Ok(Box::new(WifiData {
+            id: Uuid::new_v4(),
+            network,
+        }))
+    }
+}
+
+#[derive(Debug, Default)]
+struct WifiNetwork {
+    ssid: String,
+    /// The authentication type, or `None` for open networks.
+    security: Option<String>,
+    password: Option<String>,
+    hidden: bool,
+    /// Fields without special handling, like EAP settings.
+    other: Vec<(String, String)>,
+}
+
+impl WifiNetwork {
+    /// Parse a network from the `WIFI:T:WPA;S:ssid;P:password;;` format.
+    fn parse(input: &str) -> eyre::Result<Self> {
+        let data = input
+            .trim()
+            .strip_prefix("WIFI:")
+            .ok_or_else(|| eyre::eyre!("missing WIFI prefix"))?;
+
+        let mut network = Self::default();
+        let mut has_ssid = false;
+
+        for field in split_unescaped(data, ';') {
+            if field.is_empty() {
+                continue;
+            }
+
+            let (key, value) = field
+                .split_once(':')
+                .ok_or_else(|| eyre::eyre!("field was missing value"))?;
+            let value = unescape(value);
+
+            match key {
+                "S" => {
+                    network.ssid = value;
+                    has_ssid = true;
+                }
+                "T" if value.eq_ignore_ascii_case("nopass") || value.is_empty() => {
+                    network.security = None
+                }
+                "T" => network.security = Some(value),
+                "P" if !value.is_empty() => network.password = Some(value),
+                "P" => (),
+                "H" => network.hidden = value.eq_ignore_ascii_case("true"),
+                _ => network.other.push((key.to_string(), value)),
+            }
+        }
+
+        eyre::ensure!(has_ssid, "network was missing ssid");
+
+        Ok(network)
+    }
+
+    fn security_name(&self) -> &str {
+        self.security.as_deref().unwrap_or("None")
+    }
+}
+
+#[derive(Debug)]
+struct WifiData {
+    id: Uuid,
+    network: WifiNetwork,
+}
+
+impl WifiData {
+    fn copy_button(ui: &mut Ui, value: &str) {
+        if ui.small_button("📋").on_hover_text("Copy").clicked() {
+            ui.output_mut(|output| output.copied_text = value.to_string());
+        }
+    }
+}
+
+impl BarcodeData for WifiData {
+    fn id(&self) -> Uuid {
+        self.id
+    }
+
+    fn summary(&self) -> String {
+        self.network.ssid.clone()
+    }
+
+    fn render(&self, ui: &mut Ui) {
+        let reveal_id = Id::new(self.id).with("reveal-password");
+        let mut revealed = ui.data(|data| data.get_temp(reveal_id).unwrap_or(false));
+
+        Grid::new(self.id)
+            .num_columns(3)
+            .striped(true)
+            .spacing([20.0, 4.0])
+            .show(ui, |ui| {
+                ui.strong("SSID");
+                ui.label(&self.network.ssid);
+                Self::copy_button(ui, &self.network.ssid);
+                ui.end_row();
+
+                ui.strong("Security");
+                ui.label(self.network.security_name());
+                Self::copy_button(ui, self.network.security_name());
+                ui.end_row();
+
+                if let Some(password) = &self.network.password {
+                    ui.strong("Password");
+                    ui.horizontal(|ui| {
+                        if revealed {
+                            ui.label(RichText::new(password).monospace());
+                        } else {
+                            ui.label(
+                                RichText::new("•".repeat(password.chars().count())).monospace(),
+                            );
+                        }
+
+                        if ui
+                            .small_button(if revealed { "Hide" } else { "Reveal" })
+                            .clicked()
+                        {
+                            revealed = !revealed;
+                        }
+                    });
+                    Self::copy_button(ui, password);
+                    ui.end_row();
+                }
+
+                ui.strong("Hidden");
+                let hidden = if self.network.hidden { "Yes" } else { "No" };
+                ui.label(hidden);
+                Self::copy_button(ui, hidden);
+                ui.end_row();
+
+                for (key, value) in &self.network.other {
+                    ui.strong(key);
+                    ui.label(value);
+                    Self::copy_button(ui, value);
+                    ui.end_row();
+                }
+            });
+
+        ui.data_mut(|data| data.insert_temp(reveal_id, revealed));
+    }
+
+    fn raw_data(&self) -> Option<&serde_json::Value> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_network() {
+        let network = WifiNetwork::parse("WIFI:T:WPA;S:Home Network;P:hunter2;H:true;;").unwrap();
+
+        assert_eq!(network.ssid, "Home Network");
+        assert_eq!(network.security.as_deref(), Some("WPA"));
+        assert_eq!(network.password.as_deref(), Some("hunter2"));
+        assert!(network.hidden);
+        assert!(network.other.is_empty());
+    }
+
+    #[test]
+    fn parses_fields_in_any_order() {
+        let network = WifiNetwork::parse("WIFI:P:secret;S:Office;T:WEP;;").unwrap();
+
+        assert_eq!(network.ssid, "Office");
+        assert_eq!(network.security.as_deref(), Some("WEP"));
+        assert_eq!(network.password.as_deref(), Some("secret"));
+        assert!(!network.hidden);
+    }
+
+    #[test]
+    fn parses_open_networks() {
+        for input in [
+            "WIFI:S:Cafe;T:nopass;;",
+            "WIFI:S:Cafe;T:NOPASS;P:;;",
+            "WIFI:S:Cafe;T:;;",
+            "WIFI:S:Cafe;;",
+        ] {
+            let network = WifiNetwork::parse(input).unwrap();
+
+            assert_eq!(network.ssid, "Cafe", "{input}");
+            assert_eq!(network.security, None, "{input}");
+            assert_eq!(network.password, None, "{input}");
+            assert_eq!(network.security_name(), "None");
+        }
+    }
+
+    #[test]
+    fn parses_escaped_values() {
+        let network = WifiNetwork::parse(r#"WIFI:S:a\;b\:c;T:WPA;P:p\\w\"d\;;;"#).unwrap();
+
+        assert_eq!(network.ssid, "a;b:c");
+        assert_eq!(network.password.as_deref(), Some(r#"p\w"d;"#));
+    }
+
+    #[test]
+    fn parses_quoted_values() {
+        let network = WifiNetwork::parse(r#"WIFI:S:"12345";T:WPA;P:"pass;word";;"#);
+        // Quotes don't protect separators, so they must still be escaped.
+        assert!(network.is_err());
+
+        let network = WifiNetwork::parse(r#"WIFI:S:"12345";T:WPA;P:"pass\;word";;"#).unwrap();
+        assert_eq!(network.ssid, "12345");
+        assert_eq!(network.password.as_deref(), Some("pass;word"));
+    }
+
+    #[test]
+    fn keeps_other_fields() {
+        let network =
+            WifiNetwork::parse("WIFI:T:WPA2-EAP;S:Corp;E:PEAP;PH2:MSCHAPV2;I:user;;").unwrap();
+
+        assert_eq!(network.security.as_deref(), Some("WPA2-EAP"));
+        assert_eq!(
+            network.other,
+            [
+                ("E".to_string(), "PEAP".to_string()),
+                ("PH2".to_string(), "MSCHAPV2".to_string()),
+                ("I".to_string(), "user".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_networks() {
+        for input in [
+            "",
+            "S:Home;;",
+            "wifi:S:Home;;",
+            "WIFI:T:WPA;P:secret;;",
+            "WIFI:S:Home;invalid;;",
+        ] {
+            assert!(WifiNetwork::parse(input).is_err(), "{input}");
+        }
+    }
+}
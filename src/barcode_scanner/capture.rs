@@ -0,0 +1,265 @@
+use std::{collections::VecDeque, time::Duration};
+
+use serde::{Deserialize, Serialize};
+use tokio::{
+    fs::File,
+    io::{AsyncRead, AsyncReadExt, AsyncWriteExt},
+    time::Instant,
+};
+
+use super::ScannerOptions;
+
+/// Something that produces raw chunks of data from a scanner, such as HID
+/// input reports or bytes from a serial port.
+pub(crate) trait RawReader {
+    async fn read(&mut self, buf: &mut [u8]) -> eyre::Result<usize>;
+}
+
+impl RawReader for async_hid::Device {
+    async fn read(&mut self, buf: &mut [u8]) -> eyre::Result<usize> {
+        Ok(self.read_input_report(buf).await?)
+    }
+}
+
+/// Reads chunks from anything implementing [`AsyncRead`].
+pub(crate) struct StreamReader<R>(pub(crate) R);
+
+impl<R: AsyncRead + Unpin> RawReader for StreamReader<R> {
+    async fn read(&mut self, buf: &mut [u8]) -> eyre::Result<usize> {
+        Ok(self.0.read(buf).await?)
+    }
+}
+
+/// How the data in a capture should be parsed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) enum CaptureKind {
+    HidReport,
+    Serial,
+}
+
+/// The first line of a capture file, describing how to replay it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct CaptureHeader {
+    pub(crate) kind: CaptureKind,
+    pub(crate) options: ScannerOptions,
+}
+
+/// A single chunk of data in a capture file.
+#[derive(Debug, Serialize, Deserialize)]
+struct CaptureRecord {
+    /// Milliseconds since the capture started.
+    t: u64,
+    /// Hex-encoded data.
+    data: String,
+}
+
+/// Wraps a reader, writing every chunk read to a capture file if one was
+/// configured.
+pub(crate) struct CapturingReader<R> {
+    inner: R,
+    capture: Option<(File, Instant)>,
+}
+
+impl<R: RawReader> CapturingReader<R> {
+    pub(crate) async fn new(
+        inner: R,
+        kind: CaptureKind,
+        options: &ScannerOptions,
+    ) -> eyre::Result<Self> {
+        let Some(path) = options.capture_path.as_deref() else {
+            return Ok(Self {
+                inner,
+                capture: None,
+            });
+        };
+
+        tracing::info!(path, "capturing scanner data");
+
+        let mut file = File::create(path).await?;
+
+        let header = CaptureHeader {
+            kind,
+            options: options.clone(),
+        };
+        let mut line = serde_json::to_vec(&header)?;
+        line.push(b'\n');
+        file.write_all(&line).await?;
+
+        Ok(Self {
+            inner,
+            capture: Some((file, Instant::now())),
+        })
+    }
+}
+
+impl<R: RawReader> RawReader for CapturingReader<R> {
+    async fn read(&mut self, buf: &mut [u8]) -> eyre::Result<usize> {
+        let size = self.inner.read(buf).await?;
+
+        if let Some((file, started)) = self.capture.as_mut() {
+            let record = CaptureRecord {
+                t: started.elapsed().as_millis() as u64,
+                data: hex::encode(&buf[0..size]),
+            };
+
+            let mut line = serde_json::to_vec(&record)?;
+            line.push(b'\n');
+            file.write_all(&line).await?;
+            file.flush().await?;
+        }
+
+        Ok(size)
+    }
+}
+
+/// Reads chunks from a capture file, with the original timing scaled by the
+/// replay speed.
+pub(crate) struct ReplayReader {
+    records: VecDeque<CaptureRecord>,
+    /// How much faster than the original to replay, or 0 for no delay.
+    speed: f32,
+    started: Instant,
+}
+
+impl ReplayReader {
+    pub(crate) async fn open(path: &str, speed: f32) -> eyre::Result<(CaptureHeader, Self)> {
+        let contents = tokio::fs::read_to_string(path).await?;
+        let mut lines = contents.lines().filter(|line| !line.trim().is_empty());
+
+        let header: CaptureHeader = serde_json::from_str(
+            lines
+                .next()
+                .ok_or_else(|| eyre::eyre!("capture file was empty"))?,
+        )?;
+
+        let records = lines.map(serde_json::from_str).collect::<Result<_, _>>()?;
+
+        Ok((
+            header,
+            Self {
+                records,
+                speed,
+                started: Instant::now(),
+            },
+        ))
+    }
+}
+
+impl RawReader for ReplayReader {
+    async fn read(&mut self, buf: &mut [u8]) -> eyre::Result<usize> {
+        let Some(record) = self.records.front() else {
+            tracing::info!("replay finished");
+            return std::future::pending().await;
+        };
+
+        // Reads are raced against idle timeouts, so the record must stay
+        // queued until the delay has finished.
+        if self.speed > 0.0 {
+            let offset = Duration::from_millis(record.t).div_f32(self.speed);
+            tokio::time::sleep_until(self.started + offset).await;
+        }
+
+        let record = self.records.pop_front().expect("record was checked");
+        let data = hex::decode(record.data)?;
+        let size = data.len().min(buf.len());
+        buf[0..size].copy_from_slice(&data[0..size]);
+
+        Ok(size)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::barcode_scanner::{framing::Terminator, HidType};
+
+    use super::*;
+
+    fn fixture(name: &str) -> String {
+        format!(
+            "{}/tests/fixtures/captures/{name}",
+            env!("CARGO_MANIFEST_DIR")
+        )
+    }
+
+    async fn read_all(reader: &mut ReplayReader) -> Vec<Vec<u8>> {
+        let mut chunks = Vec::new();
+        let mut buf = [0u8; 256];
+
+        while !reader.records.is_empty() {
+            let size = reader.read(&mut buf).await.unwrap();
+            chunks.push(buf[0..size].to_vec());
+        }
+
+        chunks
+    }
+
+    #[tokio::test]
+    async fn opens_capture() {
+        let (header, mut reader) = ReplayReader::open(&fixture("serial.jsonl"), 0.0)
+            .await
+            .unwrap();
+
+        assert_eq!(header.kind, CaptureKind::Serial);
+        assert_eq!(header.options.framing.terminators, [Terminator::Cr]);
+        assert_eq!(
+            read_all(&mut reader).await,
+            [
+                b"]C0ABC".to_vec(),
+                b"-123\r]E0400638".to_vec(),
+                b"1333931\r".to_vec()
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn rejects_invalid_captures() {
+        let path = std::env::temp_dir().join(format!("capture-{}.jsonl", uuid::Uuid::new_v4()));
+        let path = path.to_str().unwrap();
+
+        tokio::fs::write(path, "").await.unwrap();
+        assert!(ReplayReader::open(path, 0.0).await.is_err());
+
+        tokio::fs::write(path, "{\"kind\":\"Serial\",\"options\":{}}\n{\"t\":0}\n")
+            .await
+            .unwrap();
+        assert!(ReplayReader::open(path, 0.0).await.is_err());
+
+        tokio::fs::remove_file(path).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn replays_what_was_captured() {
+        let path = std::env::temp_dir().join(format!("capture-{}.jsonl", uuid::Uuid::new_v4()));
+        let options = ScannerOptions {
+            capture_path: Some(path.to_str().unwrap().to_string()),
+            hid_type: HidType::Pos,
+            ..Default::default()
+        };
+
+        let data = b"\x02\x15hello".as_slice();
+        let mut reader = CapturingReader::new(StreamReader(data), CaptureKind::HidReport, &options)
+            .await
+            .unwrap();
+
+        let mut buf = [0u8; 4];
+        let mut chunks = Vec::new();
+        loop {
+            let size = reader.read(&mut buf).await.unwrap();
+            chunks.push(buf[0..size].to_vec());
+            if size == 0 {
+                break;
+            }
+        }
+        drop(reader);
+
+        let (header, mut replay) =
+            ReplayReader::open(options.capture_path.as_deref().unwrap(), 0.0)
+                .await
+                .unwrap();
+        assert_eq!(header.kind, CaptureKind::HidReport);
+        assert_eq!(header.options.hid_type, HidType::Pos);
+        assert_eq!(read_all(&mut replay).await, chunks);
+
+        tokio::fs::remove_file(path).await.unwrap();
+    }
+}
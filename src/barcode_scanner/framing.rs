@@ -0,0 +1,344 @@
+use std::time::Duration;
+
+use enum_iterator::Sequence;
+use serde::{Deserialize, Serialize};
+
+use super::{symbology::strip_part_header, Scan, Symbology};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Sequence, Serialize, Deserialize)]
+pub(crate) enum Terminator {
+    Cr,
+    Lf,
+    Tab,
+    Etx,
+    Eot,
+}
+
+impl Terminator {
+    fn byte(&self) -> u8 {
+        match self {
+            Self::Cr => b'\r',
+            Self::Lf => b'\n',
+            Self::Tab => b'\t',
+            Self::Etx => 0x03,
+            Self::Eot => 0x04,
+        }
+    }
+}
+
+impl std::fmt::Display for Terminator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Cr => write!(f, "CR"),
+            Self::Lf => write!(f, "LF"),
+            Self::Tab => write!(f, "Tab"),
+            Self::Etx => write!(f, "ETX"),
+            Self::Eot => write!(f, "EOT"),
+        }
+    }
+}
+
+/// How the stream of data from a scanner is split into individual scans.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct FramingConfig {
+    /// End a scan after no data was received for this many milliseconds.
+    pub(crate) idle_timeout_ms: Option<u64>,
+    /// Characters that end a scan, they are not included in the scan.
+    pub(crate) terminators: Vec<Terminator>,
+    /// Only accept data between STX and ETX characters.
+    pub(crate) stx_etx_envelope: bool,
+    /// Text removed from the start of a scan, if present. This is checked
+    /// after removing any AIM symbology identifier.
+    pub(crate) prefix: String,
+    /// Text removed from the end of a scan, if present.
+    pub(crate) suffix: String,
+    /// End a scan once it reaches this many bytes, if nonzero.
+    pub(crate) max_length: usize,
+    /// Treat a leading `[n/m]` as the position of the scan in a multi-part
+    /// set.
+    pub(crate) part_counter: bool,
+}
+
+impl Default for FramingConfig {
+    fn default() -> Self {
+        Self {
+            idle_timeout_ms: Some(50),
+            terminators: Vec::new(),
+            stx_etx_envelope: false,
+            prefix: String::new(),
+            suffix: String::new(),
+            max_length: 0,
+            part_counter: false,
+        }
+    }
+}
+
+impl FramingConfig {
+    /// If scans can be ended by the framing alone. Without this, data is only
+    /// split into scans when the source marks the end of a message.
+    pub(crate) fn ends_scans(&self) -> bool {
+        self.idle_timeout_ms.is_some()
+            || !self.terminators.is_empty()
+            || self.stx_etx_envelope
+            || self.max_length > 0
+    }
+}
+
+/// Splits incoming data into scans according to a [`FramingConfig`].
+#[derive(Debug)]
+pub(crate) struct Framer {
+    config: FramingConfig,
+    value: Vec<u8>,
+    in_envelope: bool,
+}
+
+impl Framer {
+    const STX: u8 = 0x02;
+    const ETX: u8 = 0x03;
+
+    pub(crate) fn new(config: FramingConfig) -> Self {
+        Self {
+            config,
+            value: Vec::new(),
+            in_envelope: false,
+        }
+    }
+
+    /// The idle timeout, if scans should be ended after a period without data.
+    pub(crate) fn idle_timeout(&self) -> Option<Duration> {
+        self.config.idle_timeout_ms.map(Duration::from_millis)
+    }
+
+    /// Add data to the current scan, returning any scans that were completed.
+    pub(crate) fn push(&mut self, data: &[u8]) -> Vec<Scan> {
+        let mut frames = Vec::new();
+
+        for byte in data.iter().copied() {
+            if self.config.stx_etx_envelope {
+                match byte {
+                    Self::STX => {
+                        self.in_envelope = true;
+                        self.value.clear();
+                        continue;
+                    }
+                    Self::ETX if self.in_envelope => {
+                        self.in_envelope = false;
+                        frames.extend(self.take_frame());
+                        continue;
+                    }
+                    _ if !self.in_envelope => {
+                        tracing::trace!(byte, "discarding data outside of envelope");
+                        continue;
+                    }
+                    _ => (),
+                }
+            }
+
+            if self
+                .config
+                .terminators
+                .iter()
+                .any(|terminator| terminator.byte() == byte)
+            {
+                frames.extend(self.take_frame());
+                continue;
+            }
+
+            self.value.push(byte);
+
+            if self.config.max_length > 0 && self.value.len() >= self.config.max_length {
+                frames.extend(self.take_frame());
+            }
+        }
+
+        frames
+    }
+
+    /// End the current scan, returning it if there was any data.
+    pub(crate) fn flush(&mut self) -> Option<Scan> {
+        self.in_envelope = false;
+        self.take_frame()
+    }
+
+    fn take_frame(&mut self) -> Option<Scan> {
+        let value = std::mem::take(&mut self.value);
+
+        let (symbology, value) = Symbology::strip_aim_identifier(&value);
+        let (part, value) = strip_part_header(symbology, value, self.config.part_counter);
+
+        let value = value
+            .strip_prefix(self.config.prefix.as_bytes())
+            .unwrap_or(value);
+        let value = value
+            .strip_suffix(self.config.suffix.as_bytes())
+            .unwrap_or(value);
+
+        if value.is_empty() {
+            None
+        } else {
+            Some(
+                Scan::from_bytes(value.to_vec())
+                    .with_symbology(symbology)
+                    .with_part(part),
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn framer(config: FramingConfig) -> Framer {
+        Framer::new(FramingConfig {
+            idle_timeout_ms: None,
+            ..config
+        })
+    }
+
+    fn texts(scans: &[Scan]) -> Vec<&str> {
+        scans.iter().map(|scan| scan.text().unwrap()).collect()
+    }
+
+    #[test]
+    fn splits_on_terminators() {
+        let mut framer = framer(FramingConfig {
+            terminators: vec![Terminator::Cr, Terminator::Lf],
+            ..Default::default()
+        });
+
+        assert_eq!(texts(&framer.push(b"abc\r\ndef")), ["abc"]);
+        assert!(framer.push(b"ghi\t").is_empty());
+        assert_eq!(texts(&framer.push(b"\n\x04")), ["defghi\t"]);
+        assert_eq!(framer.flush().unwrap().text(), Some("\x04"));
+    }
+
+    #[test]
+    fn flushes_partial_scans() {
+        let mut framer = framer(Default::default());
+
+        assert!(framer.push(b"abc").is_empty());
+        assert_eq!(framer.flush().unwrap().text(), Some("abc"));
+        assert!(framer.flush().is_none());
+    }
+
+    #[test]
+    fn uses_envelope() {
+        let mut framer = framer(FramingConfig {
+            stx_etx_envelope: true,
+            ..Default::default()
+        });
+
+        // Data outside of the envelope is discarded, and a new STX restarts
+        // the scan.
+        assert_eq!(
+            texts(&framer.push(b"noise\x02abc\x03more\x02de\x02fg")),
+            ["abc"]
+        );
+        assert_eq!(texts(&framer.push(b"h\x03")), ["fgh"]);
+
+        // An ETX without a STX is just data outside of the envelope.
+        assert!(framer.push(b"\x03").is_empty());
+        assert!(framer.flush().is_none());
+    }
+
+    #[test]
+    fn splits_at_max_length() {
+        let mut framer = framer(FramingConfig {
+            max_length: 3,
+            ..Default::default()
+        });
+
+        assert_eq!(texts(&framer.push(b"abcdefg")), ["abc", "def"]);
+        assert_eq!(framer.flush().unwrap().text(), Some("g"));
+    }
+
+    #[test]
+    fn strips_identifiers_and_affixes() {
+        let mut framer = framer(FramingConfig {
+            terminators: vec![Terminator::Cr],
+            prefix: "PRE".to_string(),
+            suffix: "SUF".to_string(),
+            ..Default::default()
+        });
+
+        let scans = framer.push(b"]E0PRE4006381333931SUF\rPREonly\rnone\rPRESUF\r");
+        assert_eq!(texts(&scans), ["4006381333931", "only", "none"]);
+        assert_eq!(scans[0].symbology(), Some(Symbology::EanUpc));
+        assert_eq!(scans[1].symbology(), None);
+    }
+
+    #[test]
+    fn drops_empty_scans() {
+        let mut framer = framer(FramingConfig {
+            terminators: vec![Terminator::Cr, Terminator::Lf],
+            ..Default::default()
+        });
+
+        assert_eq!(texts(&framer.push(b"\r\n\r\nabc\r\n]C0\r")), ["abc"]);
+    }
+
+    #[test]
+    fn strips_part_counter() {
+        let mut framer = framer(FramingConfig {
+            terminators: vec![Terminator::Cr],
+            part_counter: true,
+            ..Default::default()
+        });
+
+        let scans = framer.push(b"[2/3]abc\r");
+        assert_eq!(texts(&scans), ["abc"]);
+
+        let part = scans[0].part().unwrap();
+        assert_eq!((part.index, part.total), (1, Some(3)));
+    }
+
+    #[test]
+    fn keeps_binary_data() {
+        let mut framer = framer(FramingConfig {
+            max_length: 4,
+            ..Default::default()
+        });
+
+        let scans = framer.push(&[0xff, 0x00, 0xfe, 0x01]);
+        assert_eq!(scans[0].data(), [0xff, 0x00, 0xfe, 0x01]);
+        assert_eq!(scans[0].text(), None);
+    }
+
+    #[test]
+    fn reports_idle_timeout() {
+        assert_eq!(
+            Framer::new(Default::default()).idle_timeout(),
+            Some(Duration::from_millis(50))
+        );
+        assert_eq!(framer(Default::default()).idle_timeout(), None);
+    }
+
+    #[test]
+    fn detects_framing_that_never_ends_scans() {
+        let never_ends = FramingConfig {
+            idle_timeout_ms: None,
+            ..Default::default()
+        };
+        assert!(!never_ends.ends_scans());
+        assert!(FramingConfig::default().ends_scans());
+
+        for config in [
+            FramingConfig {
+                terminators: vec![Terminator::Eot],
+                ..never_ends.clone()
+            },
+            FramingConfig {
+                stx_etx_envelope: true,
+                ..never_ends.clone()
+            },
+            FramingConfig {
+                max_length: 10,
+                ..never_ends.clone()
+            },
+        ] {
+            assert!(config.ends_scans(), "{config:?}");
+        }
+    }
+}
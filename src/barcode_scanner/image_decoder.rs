@@ -0,0 +1,297 @@
+use std::collections::HashMap;
+
+use image::{DynamicImage, RgbaImage};
+use rxing::{
+    common::HybridBinarizer,
+    multi::{GenericMultipleBarcodeReader, MultipleBarcodeReader},
+    BarcodeFormat, BinaryBitmap, DecodeHintType, DecodeHintValue, Exceptions, Luma8LuminanceSource,
+    MultiUseMultiFormatReader, RXingResult, RXingResultMetadataType, RXingResultMetadataValue,
+};
+
+use super::{Scan, ScanPart, Symbology};
+
+/// File extensions of images that can be decoded.
+pub(crate) const IMAGE_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "webp"];
+
+/// Find and decode every barcode in an encoded image file.
+///
+/// This is CPU intensive, so it should be run on a blocking thread.
+pub(crate) fn decode_image(data: &[u8]) -> eyre::Result<Vec<Scan>> {
+    find_barcodes(image::load_from_memory(data)?)
+}
+
+/// Find and decode every barcode in the image currently on the clipboard.
+///
+/// This blocks while reading the clipboard, so it should be run on a blocking
+/// thread.
+pub(crate) fn decode_clipboard_image() -> eyre::Result<Vec<Scan>> {
+    let image = match arboard::Clipboard::new()?.get_image() {
+        Ok(image) => image,
+        Err(arboard::Error::ContentNotAvailable) => {
+            eyre::bail!("the clipboard does not contain an image")
+        }
+        Err(err) => return Err(err.into()),
+    };
+
+    let rgba = RgbaImage::from_raw(
+        image.width as u32,
+        image.height as u32,
+        image.bytes.into_owned(),
+    )
+    .ok_or_else(|| eyre::eyre!("clipboard image had invalid size"))?;
+
+    find_barcodes(DynamicImage::ImageRgba8(rgba))
+}
+
+fn find_barcodes(image: DynamicImage) -> eyre::Result<Vec<Scan>> {
+    let luma = image.into_luma8();
+    let (width, height) = luma.dimensions();
+
+    let mut bitmap = BinaryBitmap::new(HybridBinarizer::new(Luma8LuminanceSource::new(
+        luma.into_raw(),
+        width,
+        height,
+    )));
+
+    let hints = HashMap::from([(DecodeHintType::TRY_HARDER, DecodeHintValue::TryHarder(true))]);
+
+    let mut reader = GenericMultipleBarcodeReader::new(MultiUseMultiFormatReader::default());
+    let results = match reader.decode_multiple_with_hints(&mut bitmap, &hints) {
+        Ok(results) => results,
+        Err(Exceptions::NotFoundException(_)) => return Ok(Vec::new()),
+        Err(err) => eyre::bail!("could not decode image: {err}"),
+    };
+
+    tracing::debug!(count = results.len(), "found barcodes in image");
+
+    Ok(results
+        .into_iter()
+        .map(|result| {
+            scan_data(&result)
+                .with_symbology(symbology_for_result(&result))
+                .with_part(scan_part(&result))
+        })
+        .collect())
+}
+
+/// Create a scan from the raw bytes of a result when they hold all of its
+/// content, so binary content isn't lost by converting it to text.
+fn scan_data(result: &RXingResult) -> Scan {
+    let text = result.getText();
+
+    if let Some(RXingResultMetadataValue::ByteSegments(segments)) = result
+        .getRXingResultMetadata()
+        .get(&RXingResultMetadataType::BYTE_SEGMENTS)
+    {
+        let data = segments.concat();
+
+        // Symbols mixing byte mode with other modes only have some of their
+        // content in the byte segments, so the bytes are only used if they
+        // are the same as the text or the text could not represent them.
+        if std::str::from_utf8(&data) == Ok(text) || text.contains(char::REPLACEMENT_CHARACTER) {
+            return Scan::from_bytes(data);
+        }
+    }
+
+    Scan::from_text(text.to_string())
+}
+
+/// Get where a result belongs if it was part of a QR Code Structured Append
+/// or Macro PDF417 set.
+fn scan_part(result: &RXingResult) -> Option<ScanPart> {
+    let metadata = result.getRXingResultMetadata();
+
+    if let (
+        Some(RXingResultMetadataValue::StructuredAppendSequence(sequence)),
+        Some(RXingResultMetadataValue::StructuredAppendParity(parity)),
+    ) = (
+        metadata.get(&RXingResultMetadataType::STRUCTURED_APPEND_SEQUENCE),
+        metadata.get(&RXingResultMetadataType::STRUCTURED_APPEND_PARITY),
+    ) {
+        // The sequence has the position in the upper 4 bits and the total
+        // minus one in the lower 4 bits.
+        if *sequence >= 0 {
+            let total = (sequence & 0x0f) as usize + 1;
+
+            return Some(ScanPart {
+                group: format!("qr-{parity}-{total}"),
+                index: (sequence >> 4) as usize,
+                total: Some(total),
+                per_source: false,
+            });
+        }
+    }
+
+    if let Some(RXingResultMetadataValue::Pdf417ExtraMetadata(macro_pdf)) =
+        metadata.get(&RXingResultMetadataType::PDF417_EXTRA_METADATA)
+    {
+        let index = macro_pdf.getSegmentIndex();
+
+        // The count is optional, but the last segment is always marked.
+        let total = if macro_pdf.getSegmentCount() > 0 {
+            Some(macro_pdf.getSegmentCount() as usize)
+        } else if macro_pdf.isLastSegment() {
+            Some(index + 1)
+        } else {
+            None
+        };
+
+        return Some(ScanPart {
+            group: format!("pdf417-{}", macro_pdf.getFileId()),
+            index,
+            total,
+            per_source: false,
+        });
+    }
+
+    None
+}
+
+/// Get the symbology of a result, preferring the AIM identifier from the
+/// reader as it distinguishes GS1 variants.
+fn symbology_for_result(result: &RXingResult) -> Option<Symbology> {
+    if let Some(RXingResultMetadataValue::SymbologyIdentifier(identifier)) = result
+        .getRXingResultMetadata()
+        .get(&RXingResultMetadataType::SYMBOLOGY_IDENTIFIER)
+    {
+        match Symbology::strip_aim_identifier(identifier.as_bytes()) {
+            (Some(Symbology::Other(_)) | None, _) => (),
+            (Some(symbology), _) => return Some(symbology),
+        }
+    }
+
+    symbology_for_format(result.getBarcodeFormat())
+}
+
+fn symbology_for_format(format: &BarcodeFormat) -> Option<Symbology> {
+    let symbology = match format {
+        BarcodeFormat::AZTEC => Symbology::Aztec,
+        BarcodeFormat::CODABAR => Symbology::Codabar,
+        BarcodeFormat::CODE_39 => Symbology::Code39,
+        BarcodeFormat::CODE_93 => Symbology::Code93,
+        BarcodeFormat::CODE_128 => Symbology::Code128,
+        BarcodeFormat::DATA_MATRIX => Symbology::DataMatrix,
+        BarcodeFormat::EAN_8
+        | BarcodeFormat::EAN_13
+        | BarcodeFormat::UPC_A
+        | BarcodeFormat::UPC_E
+        | BarcodeFormat::UPC_EAN_EXTENSION => Symbology::EanUpc,
+        BarcodeFormat::ITF => Symbology::Interleaved2Of5,
+        BarcodeFormat::MAXICODE => Symbology::MaxiCode,
+        BarcodeFormat::PDF_417 => Symbology::Pdf417,
+        BarcodeFormat::QR_CODE
+        | BarcodeFormat::MICRO_QR_CODE
+        | BarcodeFormat::RECTANGULAR_MICRO_QR_CODE => Symbology::QrCode,
+        BarcodeFormat::RSS_14 | BarcodeFormat::RSS_EXPANDED => Symbology::Gs1DataBar,
+        _ => return None,
+    };
+
+    Some(symbology)
+}
+
+#[cfg(test)]
+mod tests {
+    use image::GrayImage;
+    use rxing::{qrcode::QRCodeWriter, EncodeHintType, EncodeHintValue, Writer};
+
+    use super::*;
+
+    /// Render a QR code, using the smallest combination of modes for the
+    /// contents.
+    fn render_qr(contents: &str) -> DynamicImage {
+        let hints = HashMap::from([
+            (
+                EncodeHintType::QR_COMPACT,
+                EncodeHintValue::QrCompact("true".to_string()),
+            ),
+            (
+                EncodeHintType::CHARACTER_SET,
+                EncodeHintValue::CharacterSet("UTF-8".to_string()),
+            ),
+        ]);
+
+        let matrix = QRCodeWriter
+            .encode_with_hints(contents, &BarcodeFormat::QR_CODE, 200, 200, &hints)
+            .unwrap();
+
+        let image = GrayImage::from_fn(matrix.getWidth(), matrix.getHeight(), |x, y| {
+            image::Luma([if matrix.get(x, y) { 0 } else { 255 }])
+        });
+
+        DynamicImage::ImageLuma8(image)
+    }
+
+    fn result_with_segments(text: &str, segments: Vec<Vec<u8>>) -> RXingResult {
+        let mut result = RXingResult::new(text, Vec::new(), Vec::new(), BarcodeFormat::QR_CODE);
+        result.putMetadata(
+            RXingResultMetadataType::BYTE_SEGMENTS,
+            RXingResultMetadataValue::ByteSegments(segments),
+        );
+        result
+    }
+
+    #[test]
+    fn decodes_mixed_mode_qr_code() {
+        let contents = "123456789012345678901234€€€€€€€€€€";
+        let scans = find_barcodes(render_qr(contents)).unwrap();
+
+        assert_eq!(scans.len(), 1);
+        assert_eq!(scans[0].text(), Some(contents));
+        assert_eq!(scans[0].symbology(), Some(Symbology::QrCode));
+    }
+
+    #[test]
+    fn keeps_text_missing_from_byte_segments() {
+        let result = result_with_segments("12345€€€", vec!["€€€".as_bytes().to_vec()]);
+
+        assert_eq!(scan_data(&result).text(), Some("12345€€€"));
+    }
+
+    #[test]
+    fn uses_bytes_matching_text() {
+        let result = result_with_segments("ab€", vec![b"a".to_vec(), "b€".as_bytes().to_vec()]);
+
+        assert_eq!(scan_data(&result).data(), "ab€".as_bytes());
+    }
+
+    #[test]
+    fn uses_bytes_for_binary_content() {
+        let result = result_with_segments("\u{fffd}\u{fffd}", vec![vec![0xff, 0xfe]]);
+        let scan = scan_data(&result);
+
+        assert_eq!(scan.data(), [0xff, 0xfe]);
+        assert_eq!(scan.text(), None);
+    }
+
+    #[test]
+    fn uses_symbology_identifier() {
+        let cases = [
+            (BarcodeFormat::DATA_MATRIX, "]d2", Symbology::Gs1DataMatrix),
+            (BarcodeFormat::DATA_MATRIX, "]d1", Symbology::DataMatrix),
+            (BarcodeFormat::CODE_128, "]C1", Symbology::Gs1128),
+            (BarcodeFormat::CODE_128, "]C0", Symbology::Code128),
+            (BarcodeFormat::QR_CODE, "]Q3", Symbology::Gs1QrCode),
+        ];
+
+        for (format, identifier, symbology) in cases {
+            let mut result = RXingResult::new("", Vec::new(), Vec::new(), format);
+            result.putMetadata(
+                RXingResultMetadataType::SYMBOLOGY_IDENTIFIER,
+                RXingResultMetadataValue::SymbologyIdentifier(identifier.to_string()),
+            );
+
+            assert_eq!(
+                symbology_for_result(&result),
+                Some(symbology),
+                "{identifier}"
+            );
+        }
+    }
+
+    #[test]
+    fn falls_back_to_format() {
+        let result = RXingResult::new("", Vec::new(), Vec::new(), BarcodeFormat::CODE_128);
+
+        assert_eq!(symbology_for_result(&result), Some(Symbology::Code128));
+    }
+}
@@ -0,0 +1,806 @@
+use enum_iterator::Sequence;
+use enumflags2::{bitflags, BitFlags};
+use serde::{Deserialize, Serialize};
+
+/// Keys that produce the same character regardless of the selected layout.
+static COMMON_KEYS: phf::Map<u8, char> = phf::phf_map! {
+    0x28u8 => '\n',
+    0x29u8 => '\x1b',
+    0x2bu8 => '\t',
+    0x2cu8 => ' ',
+    0x54u8 => '/',
+    0x55u8 => '*',
+    0x56u8 => '-',
+    0x57u8 => '+',
+    0x58u8 => '\n',
+    0x59u8 => '1',
+    0x5au8 => '2',
+    0x5bu8 => '3',
+    0x5cu8 => '4',
+    0x5du8 => '5',
+    0x5eu8 => '6',
+    0x5fu8 => '7',
+    0x60u8 => '8',
+    0x61u8 => '9',
+    0x62u8 => '0',
+    0x67u8 => '=',
+};
+
+static US_LAYOUT: phf::Map<u8, (char, char)> = phf::phf_map! {
+    0x1eu8 => ('1', '!'),
+    0x1fu8 => ('2', '@'),
+    0x20u8 => ('3', '#'),
+    0x21u8 => ('4', '$'),
+    0x22u8 => ('5', '%'),
+    0x23u8 => ('6', '^'),
+    0x24u8 => ('7', '&'),
+    0x25u8 => ('8', '*'),
+    0x26u8 => ('9', '('),
+    0x27u8 => ('0', ')'),
+    0x2du8 => ('-', '_'),
+    0x2eu8 => ('=', '+'),
+    0x2fu8 => ('[', '{'),
+    0x30u8 => (']', '}'),
+    0x31u8 => ('\\', '|'),
+    0x32u8 => ('\\', '|'),
+    0x33u8 => (';', ':'),
+    0x34u8 => ('\'', '"'),
+    0x35u8 => ('`', '~'),
+    0x36u8 => (',', '<'),
+    0x37u8 => ('.', '>'),
+    0x38u8 => ('/', '?'),
+    0x63u8 => ('.', '.'),
+    0x64u8 => ('\\', '|'),
+};
+
+static UK_LAYOUT: phf::Map<u8, (char, char)> = phf::phf_map! {
+    0x1eu8 => ('1', '!'),
+    0x1fu8 => ('2', '"'),
+    0x20u8 => ('3', '£'),
+    0x21u8 => ('4', '$'),
+    0x22u8 => ('5', '%'),
+    0x23u8 => ('6', '^'),
+    0x24u8 => ('7', '&'),
+    0x25u8 => ('8', '*'),
+    0x26u8 => ('9', '('),
+    0x27u8 => ('0', ')'),
+    0x2du8 => ('-', '_'),
+    0x2eu8 => ('=', '+'),
+    0x2fu8 => ('[', '{'),
+    0x30u8 => (']', '}'),
+    0x31u8 => ('#', '~'),
+    0x32u8 => ('#', '~'),
+    0x33u8 => (';', ':'),
+    0x34u8 => ('\'', '@'),
+    0x35u8 => ('`', '¬'),
+    0x36u8 => (',', '<'),
+    0x37u8 => ('.', '>'),
+    0x38u8 => ('/', '?'),
+    0x63u8 => ('.', '.'),
+    0x64u8 => ('\\', '|'),
+};
+
+static UK_ALT_GR: phf::Map<u8, char> = phf::phf_map! {
+    0x21u8 => '€',
+    0x35u8 => '¦',
+};
+
+static GERMAN_LAYOUT: phf::Map<u8, (char, char)> = phf::phf_map! {
+    0x1cu8 => ('z', 'Z'),
+    0x1du8 => ('y', 'Y'),
+    0x1eu8 => ('1', '!'),
+    0x1fu8 => ('2', '"'),
+    0x20u8 => ('3', '§'),
+    0x21u8 => ('4', '$'),
+    0x22u8 => ('5', '%'),
+    0x23u8 => ('6', '&'),
+    0x24u8 => ('7', '/'),
+    0x25u8 => ('8', '('),
+    0x26u8 => ('9', ')'),
+    0x27u8 => ('0', '='),
+    0x2du8 => ('ß', '?'),
+    0x2eu8 => ('´', '`'),
+    0x2fu8 => ('ü', 'Ü'),
+    0x30u8 => ('+', '*'),
+    0x31u8 => ('#', '\''),
+    0x32u8 => ('#', '\''),
+    0x33u8 => ('ö', 'Ö'),
+    0x34u8 => ('ä', 'Ä'),
+    0x35u8 => ('^', '°'),
+    0x36u8 => (',', ';'),
+    0x37u8 => ('.', ':'),
+    0x38u8 => ('-', '_'),
+    0x63u8 => (',', ','),
+    0x64u8 => ('<', '>'),
+};
+
+static GERMAN_ALT_GR: phf::Map<u8, char> = phf::phf_map! {
+    0x08u8 => '€',
+    0x10u8 => 'µ',
+    0x14u8 => '@',
+    0x1fu8 => '²',
+    0x20u8 => '³',
+    0x24u8 => '{',
+    0x25u8 => '[',
+    0x26u8 => ']',
+    0x27u8 => '}',
+    0x2du8 => '\\',
+    0x30u8 => '~',
+    0x64u8 => '|',
+};
+
+static FRENCH_LAYOUT: phf::Map<u8, (char, char)> = phf::phf_map! {
+    0x04u8 => ('q', 'Q'),
+    0x10u8 => (',', '?'),
+    0x14u8 => ('a', 'A'),
+    0x1au8 => ('z', 'Z'),
+    0x1du8 => ('w', 'W'),
+    0x1eu8 => ('&', '1'),
+    0x1fu8 => ('é', '2'),
+    0x20u8 => ('"', '3'),
+    0x21u8 => ('\'', '4'),
+    0x22u8 => ('(', '5'),
+    0x23u8 => ('-', '6'),
+    0x24u8 => ('è', '7'),
+    0x25u8 => ('_', '8'),
+    0x26u8 => ('ç', '9'),
+    0x27u8 => ('à', '0'),
+    0x2du8 => (')', '°'),
+    0x2eu8 => ('=', '+'),
+    0x2fu8 => ('^', '¨'),
+    0x30u8 => ('$', '£'),
+    0x31u8 => ('*', 'µ'),
+    0x32u8 => ('*', 'µ'),
+    0x33u8 => ('m', 'M'),
+    0x34u8 => ('ù', '%'),
+    0x35u8 => ('²', '²'),
+    0x36u8 => (';', '.'),
+    0x37u8 => (':', '/'),
+    0x38u8 => ('!', '§'),
+    0x63u8 => (',', ','),
+    0x64u8 => ('<', '>'),
+};
+
+static FRENCH_ALT_GR: phf::Map<u8, char> = phf::phf_map! {
+    0x08u8 => '€',
+    0x1fu8 => '~',
+    0x20u8 => '#',
+    0x21u8 => '{',
+    0x22u8 => '[',
+    0x23u8 => '|',
+    0x24u8 => '`',
+    0x25u8 => '\\',
+    0x26u8 => '^',
+    0x27u8 => '@',
+    0x2du8 => ']',
+    0x2eu8 => '}',
+    0x30u8 => '¤',
+};
+
+/// Characters that are typed with dead keys on some layouts, along with the
+/// letters they combine with.
+static DEAD_KEY_COMPOSITIONS: phf::Map<char, &'static [(char, char)]> = phf::phf_map! {
+    '´' => &[('a', 'á'), ('e', 'é'), ('i', 'í'), ('o', 'ó'), ('u', 'ú'), ('A', 'Á'), ('E', 'É'), ('I', 'Í'), ('O', 'Ó'), ('U', 'Ú')],
+    '`' => &[('a', 'à'), ('e', 'è'), ('i', 'ì'), ('o', 'ò'), ('u', 'ù'), ('A', 'À'), ('E', 'È'), ('I', 'Ì'), ('O', 'Ò'), ('U', 'Ù')],
+    '^' => &[('a', 'â'), ('e', 'ê'), ('i', 'î'), ('o', 'ô'), ('u', 'û'), ('A', 'Â'), ('E', 'Ê'), ('I', 'Î'), ('O', 'Ô'), ('U', 'Û')],
+    '¨' => &[('a', 'ä'), ('e', 'ë'), ('i', 'ï'), ('o', 'ö'), ('u', 'ü'), ('y', 'ÿ'), ('A', 'Ä'), ('E', 'Ë'), ('I', 'Ï'), ('O', 'Ö'), ('U', 'Ü')],
+    '~' => &[('a', 'ã'), ('n', 'ñ'), ('o', 'õ'), ('A', 'Ã'), ('N', 'Ñ'), ('O', 'Õ')],
+};
+
+#[bitflags]
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ModifierKeys {
+    ControlLeft = 0b00000001,
+    ShiftLeft = 0b00000010,
+    AltLeft = 0b00000100,
+    GuiLeft = 0b00001000,
+    ControlRight = 0b00010000,
+    ShiftRight = 0b00100000,
+    AltRight = 0b01000000,
+    GuiRight = 0b10000000,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Sequence, Serialize, Deserialize)]
+pub(crate) enum KeyboardLayout {
+    #[default]
+    Us,
+    Uk,
+    German,
+    French,
+}
+
+impl std::fmt::Display for KeyboardLayout {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Us => write!(f, "US"),
+            Self::Uk => write!(f, "UK"),
+            Self::German => write!(f, "German (QWERTZ)"),
+            Self::French => write!(f, "French (AZERTY)"),
+        }
+    }
+}
+
+impl KeyboardLayout {
+    fn keys(&self) -> &'static phf::Map<u8, (char, char)> {
+        match self {
+            Self::Us => &US_LAYOUT,
+            Self::Uk => &UK_LAYOUT,
+            Self::German => &GERMAN_LAYOUT,
+            Self::French => &FRENCH_LAYOUT,
+        }
+    }
+
+    fn alt_gr_keys(&self) -> Option<&'static phf::Map<u8, char>> {
+        match self {
+            Self::Us => None,
+            Self::Uk => Some(&UK_ALT_GR),
+            Self::German => Some(&GERMAN_ALT_GR),
+            Self::French => Some(&FRENCH_ALT_GR),
+        }
+    }
+
+    fn is_dead_key(&self, key_byte: u8, shift: bool, alt_gr: bool) -> bool {
+        match self {
+            Self::Us | Self::Uk => false,
+            Self::German => matches!(
+                (key_byte, shift, alt_gr),
+                (0x2e, _, false) | (0x35, false, false)
+            ),
+            Self::French => matches!(
+                (key_byte, shift, alt_gr),
+                (0x2f, _, false) | (0x1f, _, true) | (0x24, _, true)
+            ),
+        }
+    }
+
+    /// Look up the character produced by a key with the given modifiers.
+    ///
+    /// Caps Lock only changes the case of letters, reversing Shift.
+    fn lookup(&self, key_byte: u8, shift: bool, caps_lock: bool, alt_gr: bool) -> Option<char> {
+        if alt_gr {
+            if let Some(alt_gr_keys) = self.alt_gr_keys() {
+                return alt_gr_keys.get(&key_byte).copied();
+            }
+        }
+
+        if let Some(key) = COMMON_KEYS.get(&key_byte) {
+            return Some(*key);
+        }
+
+        if let Some((normal, shifted)) = self.keys().get(&key_byte) {
+            let is_letter = normal.is_alphabetic() && normal.to_uppercase().eq([*shifted]);
+            return Some(if shift ^ (caps_lock && is_letter) {
+                *shifted
+            } else {
+                *normal
+            });
+        }
+
+        // All remaining letters are in the same position on every supported
+        // layout.
+        if (0x04..=0x1d).contains(&key_byte) {
+            let key = (b'a' + key_byte - 0x04) as char;
+            return Some(if shift ^ caps_lock {
+                key.to_ascii_uppercase()
+            } else {
+                key
+            });
+        }
+
+        None
+    }
+}
+
+/// Characters for the upper half of code page 437, used by Alt codes that
+/// are typed without a leading zero.
+const CP437_HIGH: &str = "ÇüéâäàåçêëèïîìÄÅÉæÆôöòûùÿÖÜ¢£¥₧ƒáíóúñÑªº¿⌐¬½¼¡«»░▒▓│┤╡╢╖╕╣║╗╝╜╛┐└┴┬├─┼╞╟╚╔╩╦╠═╬╧╨╤╥╙╘╒╓╫╪┘┌█▄▌▐▀αßΓπΣσµτΦΘΩδ∞φε∩≡±≥≤⌠⌡÷≈°∙·√ⁿ²■\u{a0}";
+
+/// Characters for 0x80 through 0x9F of Windows-1252, used by Alt codes that
+/// are typed with a leading zero. Undefined positions fall back to the C1
+/// control characters.
+const CP1252_C1: &str = "€\u{81}‚ƒ„…†‡ˆ‰Š‹Œ\u{8d}Ž\u{8f}\u{90}‘’“”•–—˜™š›œ\u{9d}žŸ";
+
+/// Converts HID boot keyboard input reports into the text they represent.
+///
+/// Reports contain the full set of currently pressed keys, so the decoder
+/// tracks what was pressed in the previous report to only emit newly pressed
+/// keys.
+///
+/// Scanners emit ASCII control characters either as Ctrl+key chords or as
+/// Alt+keypad code sequences, both of which are translated back into the
+/// original bytes.
+#[derive(Debug)]
+pub(crate) struct KeyboardDecoder {
+    layout: KeyboardLayout,
+    pressed_keys: Vec<u8>,
+    caps_lock: bool,
+    dead_key: Option<char>,
+    alt_code: Option<String>,
+}
+
+impl KeyboardDecoder {
+    const BACKSPACE: u8 = 0x2a;
+    const SPACE: u8 = 0x2c;
+    const CAPS_LOCK: u8 = 0x39;
+
+    pub(crate) fn new(layout: KeyboardLayout) -> Self {
+        Self {
+            layout,
+            pressed_keys: Vec::with_capacity(6),
+            caps_lock: false,
+            dead_key: None,
+            alt_code: None,
+        }
+    }
+
+    /// Process an input report, appending any typed characters to value.
+    pub(crate) fn process_report(&mut self, report: &[u8], value: &mut String) {
+        if report.len() < 3 {
+            return;
+        }
+
+        let mod_keys = BitFlags::<ModifierKeys>::from_bits_truncate(report[0]);
+
+        // Layouts without AltGr treat both Alt keys the same.
+        let has_alt_gr = self.layout.alt_gr_keys().is_some();
+        let alt_gr = has_alt_gr && mod_keys.contains(ModifierKeys::AltRight);
+        let alt = mod_keys.contains(ModifierKeys::AltLeft)
+            || (!has_alt_gr && mod_keys.contains(ModifierKeys::AltRight));
+
+        let modifiers = Modifiers {
+            shift: mod_keys.intersects(ModifierKeys::ShiftLeft | ModifierKeys::ShiftRight),
+            control: mod_keys.intersects(ModifierKeys::ControlLeft | ModifierKeys::ControlRight),
+            alt,
+            alt_gr,
+        };
+
+        // Releasing Alt completes an Alt code sequence.
+        if !modifiers.alt {
+            if let Some(alt_code) = self.alt_code.take() {
+                match Self::alt_code_char(&alt_code) {
+                    Some(ch) => value.push(ch),
+                    None => tracing::warn!(alt_code, "got invalid alt code"),
+                }
+            }
+        }
+
+        let keys: Vec<u8> = report[2..]
+            .iter()
+            .copied()
+            .filter(|key_byte| *key_byte > 0x03)
+            .collect();
+
+        // Iterate through each newly pressed key, combine with modifiers, and
+        // append to the input buffer.
+        for key_byte in keys.iter().copied() {
+            if self.pressed_keys.contains(&key_byte) {
+                continue;
+            }
+
+            self.process_key(key_byte, modifiers, value);
+        }
+
+        self.pressed_keys = keys;
+    }
+
+    fn process_key(&mut self, key_byte: u8, modifiers: Modifiers, value: &mut String) {
+        if key_byte == Self::BACKSPACE {
+            value.pop();
+            return;
+        }
+
+        // Reports don't include the lock state, so it has to be tracked from
+        // key presses.
+        if key_byte == Self::CAPS_LOCK {
+            self.caps_lock = !self.caps_lock;
+            return;
+        }
+
+        if modifiers.alt {
+            if let Some(digit) = Self::keypad_digit(key_byte) {
+                self.alt_code.get_or_insert_with(String::new).push(digit);
+                return;
+            }
+        }
+
+        let Some(key) =
+            self.layout
+                .lookup(key_byte, modifiers.shift, self.caps_lock, modifiers.alt_gr)
+        else {
+            tracing::warn!(key_byte, "got unknown keycode");
+            return;
+        };
+
+        if modifiers.control && !modifiers.alt_gr {
+            match Self::control_char(key) {
+                Some(ch) => value.push(ch),
+                None => tracing::warn!(key_byte, "got unknown control chord"),
+            }
+
+            return;
+        }
+
+        if let Some(dead_key) = self.dead_key.take() {
+            // A dead key followed by a space produces the accent itself,
+            // otherwise it may be combined with the following letter.
+            if key_byte == Self::SPACE {
+                value.push(dead_key);
+            } else if let Some((_, composed)) = DEAD_KEY_COMPOSITIONS
+                .get(&dead_key)
+                .and_then(|compositions| compositions.iter().find(|(base, _)| *base == key))
+            {
+                value.push(*composed);
+            } else {
+                value.push(dead_key);
+                value.push(key);
+            }
+
+            return;
+        }
+
+        if self
+            .layout
+            .is_dead_key(key_byte, modifiers.shift, modifiers.alt_gr)
+        {
+            self.dead_key = Some(key);
+            return;
+        }
+
+        value.push(key);
+    }
+
+    fn keypad_digit(key_byte: u8) -> Option<char> {
+        match key_byte {
+            0x59..=0x61 => Some((b'1' + key_byte - 0x59) as char),
+            0x62 => Some('0'),
+            _ => None,
+        }
+    }
+
+    /// Convert the character typed while holding Ctrl into the control
+    /// character it represents, following the usual terminal conventions.
+    /// Keys that already produce a control character, like Enter, are
+    /// unchanged.
+    fn control_char(key: char) -> Option<char> {
+        let byte = match key.to_ascii_uppercase() {
+            ch if ch.is_ascii_control() => ch as u8,
+            ch @ ('@'..='_') => ch as u8 - b'@',
+            '2' | ' ' => 0x00,
+            '3' => 0x1b,
+            '4' => 0x1c,
+            '5' => 0x1d,
+            '6' => 0x1e,
+            '7' | '-' | '/' => 0x1f,
+            '8' | '?' => 0x7f,
+            _ => return None,
+        };
+
+        Some(byte as char)
+    }
+
+    /// Convert the digits of an Alt code into a character. Codes with a
+    /// leading zero use Windows-1252, all others use code page 437.
+    fn alt_code_char(alt_code: &str) -> Option<char> {
+        let code: u32 = alt_code.parse().ok()?;
+
+        match code {
+            0..=0x7f => char::from_u32(code),
+            0x80..=0x9f if alt_code.starts_with('0') => CP1252_C1.chars().nth(code as usize - 0x80),
+            0xa0..=0xff if alt_code.starts_with('0') => char::from_u32(code),
+            0x80..=0xff => CP437_HIGH.chars().nth(code as usize - 0x80),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Modifiers {
+    shift: bool,
+    control: bool,
+    alt: bool,
+    alt_gr: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SHIFT: u8 = 0b0000_0010;
+    const ALT_GR: u8 = 0b0100_0000;
+
+    /// Press and release each key, returning the decoded text.
+    fn type_keys(decoder: &mut KeyboardDecoder, keys: &[(u8, u8)]) -> String {
+        let mut value = String::new();
+
+        for (modifiers, key) in keys.iter().copied() {
+            decoder.process_report(&[modifiers, 0, key, 0, 0, 0, 0, 0], &mut value);
+            decoder.process_report(&[0; 8], &mut value);
+        }
+
+        value
+    }
+
+    fn decode(layout: KeyboardLayout, keys: &[(u8, u8)]) -> String {
+        type_keys(&mut KeyboardDecoder::new(layout), keys)
+    }
+
+    #[test]
+    fn decodes_us_layout() {
+        assert_eq!(
+            decode(
+                KeyboardLayout::Us,
+                &[
+                    (SHIFT, 0x0b),
+                    (0, 0x08),
+                    (0, 0x36),
+                    (0, 0x2c),
+                    (SHIFT, 0x33),
+                    (0, 0x38),
+                    (SHIFT, 0x1f),
+                    (SHIFT, 0x34),
+                    (0, 0x2d),
+                    (0, 0x27),
+                    (0, 0x28),
+                ]
+            ),
+            "He, :/@\"-0\n"
+        );
+    }
+
+    #[test]
+    fn decodes_uk_layout() {
+        assert_eq!(
+            decode(
+                KeyboardLayout::Uk,
+                &[
+                    (SHIFT, 0x1f),
+                    (SHIFT, 0x20),
+                    (SHIFT, 0x34),
+                    (0, 0x31),
+                    (ALT_GR, 0x21),
+                ]
+            ),
+            "\"£@#€"
+        );
+    }
+
+    #[test]
+    fn decodes_german_layout() {
+        assert_eq!(
+            decode(
+                KeyboardLayout::German,
+                &[
+                    (0, 0x1c),
+                    (0, 0x1d),
+                    (0, 0x2d),
+                    (SHIFT, 0x2f),
+                    (0, 0x38),
+                    (ALT_GR, 0x14),
+                    (ALT_GR, 0x25),
+                    (SHIFT, 0x24),
+                ]
+            ),
+            "zyßÜ-@[/"
+        );
+    }
+
+    #[test]
+    fn decodes_french_layout() {
+        assert_eq!(
+            decode(
+                KeyboardLayout::French,
+                &[
+                    (0, 0x14),
+                    (0, 0x04),
+                    (0, 0x33),
+                    (0, 0x1f),
+                    (SHIFT, 0x1f),
+                    (0, 0x37),
+                    (ALT_GR, 0x27),
+                ]
+            ),
+            "aqmé2:@"
+        );
+    }
+
+    #[test]
+    fn decodes_keypad_on_every_layout() {
+        for layout in enum_iterator::all::<KeyboardLayout>() {
+            assert_eq!(
+                decode(layout, &[(0, 0x59), (0, 0x62), (0, 0x56), (0, 0x58)]),
+                "10-\n",
+                "{layout}"
+            );
+        }
+    }
+
+    #[test]
+    fn composes_dead_keys() {
+        // German acute followed by e, then circumflex followed by a space.
+        assert_eq!(
+            decode(
+                KeyboardLayout::German,
+                &[(0, 0x2e), (0, 0x08), (0, 0x35), (0, 0x2c)]
+            ),
+            "é^"
+        );
+
+        // French circumflex followed by e, then by a letter it can't combine
+        // with.
+        assert_eq!(
+            decode(
+                KeyboardLayout::French,
+                &[(0, 0x2f), (0, 0x08), (0, 0x2f), (0, 0x05)]
+            ),
+            "ê^b"
+        );
+    }
+
+    #[test]
+    fn only_emits_newly_pressed_keys() {
+        let mut decoder = KeyboardDecoder::new(KeyboardLayout::Us);
+        let mut value = String::new();
+
+        decoder.process_report(&[0, 0, 0x04, 0, 0, 0, 0, 0], &mut value);
+        decoder.process_report(&[0, 0, 0x04, 0x05, 0, 0, 0, 0], &mut value);
+        decoder.process_report(&[0, 0, 0x05, 0, 0, 0, 0, 0], &mut value);
+        decoder.process_report(&[0; 8], &mut value);
+        decoder.process_report(&[0, 0, 0x05, 0, 0, 0, 0, 0], &mut value);
+
+        assert_eq!(value, "abb");
+    }
+
+    #[test]
+    fn handles_backspace() {
+        assert_eq!(
+            decode(
+                KeyboardLayout::Us,
+                &[(0, 0x04), (0, 0x05), (0, 0x2a), (0, 0x06)]
+            ),
+            "ac"
+        );
+    }
+
+    #[test]
+    fn decodes_control_chords() {
+        const CTRL: u8 = 0b0000_0001;
+        const RIGHT_CTRL: u8 = 0b0001_0000;
+
+        // GS as Ctrl+], RS as Ctrl+Shift+6 and right Ctrl+6, and EOT as Ctrl+D.
+        assert_eq!(
+            decode(
+                KeyboardLayout::Us,
+                &[
+                    (CTRL, 0x30),
+                    (CTRL | SHIFT, 0x23),
+                    (RIGHT_CTRL, 0x23),
+                    (CTRL, 0x07),
+                    (CTRL | SHIFT, 0x07),
+                ]
+            ),
+            "\x1d\x1e\x1e\x04\x04"
+        );
+
+        // Keys that are already control characters pass through.
+        assert_eq!(
+            decode(
+                KeyboardLayout::Us,
+                &[(CTRL, 0x28), (CTRL, 0x2b), (CTRL, 0x58)]
+            ),
+            "\n\t\n"
+        );
+
+        // Chords use the character from the layout.
+        assert_eq!(
+            decode(KeyboardLayout::French, &[(CTRL, 0x14), (CTRL, 0x04)]),
+            "\x01\x11"
+        );
+    }
+
+    #[test]
+    fn ignores_unknown_control_chords() {
+        assert_eq!(
+            decode(KeyboardLayout::Us, &[(0b0000_0001, 0x1e), (0, 0x04)]),
+            "a"
+        );
+    }
+
+    #[test]
+    fn decodes_alt_codes() {
+        const ALT: u8 = 0b0000_0100;
+
+        let alt_code = |digits: &[u8]| -> Vec<(u8, u8)> {
+            digits
+                .iter()
+                .map(|digit| {
+                    let key = match digit {
+                        0 => 0x62,
+                        digit => 0x58 + digit,
+                    };
+
+                    (ALT, key)
+                })
+                .collect()
+        };
+
+        for (digits, expected) in [
+            (&[2, 9][..], "\x1d"),
+            (&[0, 0, 3, 0], "\x1e"),
+            (&[4], "\x04"),
+            (&[0, 1, 2, 8], "€"),
+            (&[1, 3, 0], "é"),
+            (&[0, 2, 3, 3], "é"),
+            (&[2, 5, 5], "\u{a0}"),
+        ] {
+            let mut decoder = KeyboardDecoder::new(KeyboardLayout::Us);
+            let mut value = String::new();
+
+            // Alt is held between each digit, so the code is only complete
+            // once it is released.
+            for (modifiers, key) in alt_code(digits) {
+                decoder.process_report(&[modifiers, 0, key, 0, 0, 0, 0, 0], &mut value);
+                decoder.process_report(&[modifiers, 0, 0, 0, 0, 0, 0, 0], &mut value);
+            }
+            assert_eq!(value, "", "{digits:?}");
+
+            decoder.process_report(&[0; 8], &mut value);
+            assert_eq!(value, expected, "{digits:?}");
+        }
+    }
+
+    #[test]
+    fn ignores_invalid_alt_codes() {
+        let mut decoder = KeyboardDecoder::new(KeyboardLayout::Us);
+        let mut value = String::new();
+
+        for key in [0x61, 0x61, 0x61] {
+            decoder.process_report(&[0b0000_0100, 0, key, 0, 0, 0, 0, 0], &mut value);
+            decoder.process_report(&[0b0000_0100, 0, 0, 0, 0, 0, 0, 0], &mut value);
+        }
+        decoder.process_report(&[0; 8], &mut value);
+
+        assert_eq!(value, "");
+        assert_eq!(type_keys(&mut decoder, &[(0, 0x04)]), "a");
+    }
+
+    #[test]
+    fn handles_caps_lock() {
+        let mut decoder = KeyboardDecoder::new(KeyboardLayout::Us);
+
+        // Only letters are affected, and Shift reverses it.
+        assert_eq!(
+            type_keys(
+                &mut decoder,
+                &[(0, 0x39), (0, 0x04), (SHIFT, 0x05), (0, 0x1e), (0, 0x36)]
+            ),
+            "Ab1,"
+        );
+
+        // Holding the key across reports only toggles it once.
+        let mut value = String::new();
+        decoder.process_report(&[0, 0, 0x39, 0, 0, 0, 0, 0], &mut value);
+        decoder.process_report(&[0, 0, 0x39, 0x06, 0, 0, 0, 0], &mut value);
+        decoder.process_report(&[0; 8], &mut value);
+        assert_eq!(value, "c");
+    }
+
+    #[test]
+    fn handles_caps_lock_for_layout_letters() {
+        assert_eq!(
+            decode(
+                KeyboardLayout::German,
+                &[(0, 0x39), (0, 0x2f), (0, 0x1c), (0, 0x2d), (0, 0x1f)]
+            ),
+            "ÜZß2"
+        );
+
+        assert_eq!(
+            decode(
+                KeyboardLayout::French,
+                &[(0, 0x39), (0, 0x14), (0, 0x33), (0, 0x1f)]
+            ),
+            "AMé"
+        );
+    }
+}
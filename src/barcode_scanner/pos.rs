@@ -0,0 +1,370 @@
+use enum_iterator::Sequence;
+use serde::{Deserialize, Serialize};
+
+/// How a Point of Sale scanner lays out its input reports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Sequence, Serialize, Deserialize)]
+pub(crate) enum PosReportFormat {
+    /// Detect the format from the first report.
+    #[default]
+    Auto,
+    /// Scanned Data Reports without a report ID.
+    Standard,
+    /// Scanned Data Reports preceded by a report ID.
+    StandardWithReportId,
+    /// A byte count followed by the data, where the first report's data starts
+    /// with `0x0215` and a full report means the data continues.
+    LengthFirst,
+}
+
+impl std::fmt::Display for PosReportFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Auto => write!(f, "Automatic"),
+            Self::Standard => write!(f, "Standard"),
+            Self::StandardWithReportId => write!(f, "Standard with Report ID"),
+            Self::LengthFirst => write!(f, "Length First"),
+        }
+    }
+}
+
+impl PosReportFormat {
+    /// Guess the format of a device from one of its reports.
+    pub(crate) fn detect(report: &[u8]) -> Self {
+        if report.get(1..3) == Some(&ScannedDataReport::LENGTH_FIRST_PREFIX) {
+            return Self::LengthFirst;
+        }
+
+        // Some platforms include the report ID, making the report one byte
+        // longer than the report size.
+        if report.len() > ScannedDataReport::REPORT_LEN {
+            return Self::StandardWithReportId;
+        }
+
+        let fits = |report: &[u8]| {
+            report.len() > ScannedDataReport::HEADER_LEN
+                && report[0] as usize + ScannedDataReport::HEADER_LEN < report.len()
+        };
+
+        match report {
+            [ScannedDataReport::REPORT_ID, rest @ ..] if fits(rest) && !fits(report) => {
+                Self::StandardWithReportId
+            }
+            [ScannedDataReport::REPORT_ID, rest @ ..] if fits(rest) => {
+                // Both layouts are possible, so prefer the one with a valid
+                // symbology identifier.
+                let without_id = ScannedDataReport::parse(report, Self::Standard, true);
+
+                match without_id {
+                    Ok(report) if report.aim_identifier().is_some() => Self::Standard,
+                    _ => Self::StandardWithReportId,
+                }
+            }
+            _ => Self::Standard,
+        }
+    }
+}
+
+/// A Scanned Data Report from a HID Point of Sale barcode scanner (usage page
+/// 0x8C, usage 0x12).
+///
+/// The report contains a byte count, three symbology identifier bytes, the
+/// decoded data, vendor specific bytes, and finally a flag indicating if the
+/// decoded data continues in the next report.
+#[derive(Debug)]
+pub(crate) struct ScannedDataReport<'a> {
+    symbology_identifiers: Option<[u8; 3]>,
+    data: &'a [u8],
+    continued: bool,
+}
+
+impl<'a> ScannedDataReport<'a> {
+    const REPORT_ID: u8 = 0x02;
+    const REPORT_LEN: usize = 64;
+
+    /// The byte count followed by the three symbology identifiers.
+    const HEADER_LEN: usize = 4;
+
+    /// The bytes at the start of the first report of length first devices.
+    const LENGTH_FIRST_PREFIX: [u8; 2] = [0x02, 0x15];
+
+    const DECODED_DATA_CONTINUED: u8 = 0b0000_0001;
+
+    /// Parse a report in a known format, where `first` is if this is the
+    /// first report of a message.
+    pub(crate) fn parse(
+        report: &'a [u8],
+        format: PosReportFormat,
+        first: bool,
+    ) -> eyre::Result<Self> {
+        let report = match format {
+            PosReportFormat::Auto => {
+                return Self::parse(report, PosReportFormat::detect(report), first)
+            }
+            PosReportFormat::LengthFirst => return Self::parse_length_first(report, first),
+            PosReportFormat::StandardWithReportId => report.get(1..).unwrap_or_default(),
+            PosReportFormat::Standard => report,
+        };
+
+        eyre::ensure!(
+            report.len() > Self::HEADER_LEN,
+            "report was too short: {}",
+            report.len()
+        );
+
+        let byte_count = report[0] as usize;
+        let flags = report[report.len() - 1];
+
+        // The byte count should always be within the decoded data, but make
+        // sure we never read into the flags.
+        let data_end = (Self::HEADER_LEN + byte_count).min(report.len() - 1);
+
+        Ok(Self {
+            symbology_identifiers: Some([report[1], report[2], report[3]]),
+            data: &report[Self::HEADER_LEN..data_end],
+            continued: flags & Self::DECODED_DATA_CONTINUED != 0,
+        })
+    }
+
+    fn parse_length_first(report: &'a [u8], first: bool) -> eyre::Result<Self> {
+        eyre::ensure!(report.len() > 1, "report was too short: {}", report.len());
+
+        let byte_count = report[0] as usize;
+        let data = &report[1..(byte_count + 1).min(report.len())];
+
+        let data = if first {
+            data.strip_prefix(&Self::LENGTH_FIRST_PREFIX)
+                .unwrap_or(data)
+        } else {
+            data
+        };
+
+        Ok(Self {
+            symbology_identifiers: None,
+            data,
+            // A report filled with data means there's more to come.
+            continued: byte_count >= report.len() - 1,
+        })
+    }
+
+    /// The AIM code character and modifier from the symbology identifiers.
+    ///
+    /// Scanners either fill the identifiers with a complete AIM identifier,
+    /// including the leading `]`, or with the code character and modifier
+    /// followed by a vendor specific identifier.
+    pub(crate) fn aim_identifier(&self) -> Option<(u8, u8)> {
+        match self.symbology_identifiers? {
+            [b']', code, modifier] | [code, modifier, _]
+                if code.is_ascii_alphabetic() && modifier.is_ascii_alphanumeric() =>
+            {
+                Some((code, modifier))
+            }
+            _ => None,
+        }
+    }
+
+    pub(crate) fn data(&self) -> &'a [u8] {
+        self.data
+    }
+
+    /// If the decoded data continues in the next report.
+    pub(crate) fn continued(&self) -> bool {
+        self.continued
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Build a full size report with the given data and flags.
+    fn standard_report(identifiers: &[u8; 3], data: &[u8], continued: bool) -> Vec<u8> {
+        let mut report = vec![data.len() as u8];
+        report.extend_from_slice(identifiers);
+        report.extend_from_slice(data);
+        report.resize(ScannedDataReport::REPORT_LEN - 1, 0);
+        report.push(continued.into());
+        report
+    }
+
+    fn with_report_id(report: Vec<u8>) -> Vec<u8> {
+        [vec![ScannedDataReport::REPORT_ID], report].concat()
+    }
+
+    fn length_first_report(data: &[u8]) -> Vec<u8> {
+        let mut report = vec![data.len() as u8];
+        report.extend_from_slice(data);
+        report.resize(ScannedDataReport::REPORT_LEN, 0);
+        report
+    }
+
+    fn parse(
+        report: &[u8],
+        format: PosReportFormat,
+        first: bool,
+    ) -> (Option<(u8, u8)>, &[u8], bool) {
+        let report = ScannedDataReport::parse(report, format, first).unwrap();
+        (report.aim_identifier(), report.data(), report.continued())
+    }
+
+    #[test]
+    fn parses_standard() {
+        let report = standard_report(b"]E0", b"4006381333931", false);
+
+        assert_eq!(PosReportFormat::detect(&report), PosReportFormat::Standard);
+        assert_eq!(
+            parse(&report, PosReportFormat::Standard, true),
+            (Some((b'E', b'0')), b"4006381333931".as_slice(), false)
+        );
+    }
+
+    #[test]
+    fn parses_standard_with_report_id() {
+        let report = with_report_id(standard_report(b"Q1\x00", b"https://example.com", true));
+
+        assert_eq!(
+            PosReportFormat::detect(&report),
+            PosReportFormat::StandardWithReportId
+        );
+        assert_eq!(
+            parse(&report, PosReportFormat::StandardWithReportId, true),
+            (Some((b'Q', b'1')), b"https://example.com".as_slice(), true)
+        );
+    }
+
+    #[test]
+    fn parses_length_first() {
+        let first = length_first_report(&[&[0x02, 0x15], [b'A'; 61].as_slice()].concat());
+        let last = length_first_report(b"BC");
+
+        assert_eq!(
+            PosReportFormat::detect(&first),
+            PosReportFormat::LengthFirst
+        );
+        assert_eq!(
+            parse(&first, PosReportFormat::LengthFirst, true),
+            (None, [b'A'; 61].as_slice(), true)
+        );
+        assert_eq!(
+            parse(&last, PosReportFormat::LengthFirst, false),
+            (None, b"BC".as_slice(), false)
+        );
+
+        // The prefix is only removed from the first report.
+        let continued = length_first_report(&[0x02, 0x15, b'C']);
+        assert_eq!(
+            parse(&continued, PosReportFormat::LengthFirst, false).1,
+            [0x02, 0x15, b'C']
+        );
+    }
+
+    #[test]
+    fn parses_automatically() {
+        for (report, data) in [
+            (standard_report(b"]E0", b"123", false), b"123".as_slice()),
+            (
+                with_report_id(standard_report(b"]E0", b"456", false)),
+                b"456".as_slice(),
+            ),
+            (length_first_report(b"\x02\x15789"), b"789".as_slice()),
+        ] {
+            assert_eq!(parse(&report, PosReportFormat::Auto, true).1, data);
+        }
+    }
+
+    #[test]
+    fn detects_short_reports_with_report_id() {
+        // The identifiers are only valid when skipping the report ID.
+        let report = [0x02, 0x03, b']', b'E', b'0', b'1', b'2', b'3', 0x00];
+        assert_eq!(
+            PosReportFormat::detect(&report),
+            PosReportFormat::StandardWithReportId
+        );
+        assert_eq!(
+            parse(&report, PosReportFormat::Auto, true),
+            (Some((b'E', b'0')), b"123".as_slice(), false)
+        );
+
+        // The byte count would read into the flags without the report ID.
+        let report = [0x02, 0x00, b'E', b'0', b'0', 0x00];
+        assert_eq!(
+            PosReportFormat::detect(&report),
+            PosReportFormat::StandardWithReportId
+        );
+    }
+
+    #[test]
+    fn prefers_valid_identifiers() {
+        // A byte count of 2 looks like the report ID, but the rest of the
+        // report only makes sense without one.
+        let report = standard_report(b"Q1\x00", b"AB", false);
+        assert_eq!(
+            PosReportFormat::detect(&report[..16]),
+            PosReportFormat::Standard
+        );
+        assert_eq!(
+            parse(&report[..16], PosReportFormat::Auto, true),
+            (Some((b'Q', b'1')), b"AB".as_slice(), false)
+        );
+    }
+
+    #[test]
+    fn handles_truncated_reports() {
+        // The byte count is larger than the report, so the data stops before
+        // the flags.
+        let report = [0x32, b']', b'E', b'0', b'1', b'2', b'3', 0x01];
+        assert_eq!(
+            parse(&report, PosReportFormat::Standard, true),
+            (Some((b'E', b'0')), b"123".as_slice(), true)
+        );
+
+        let report = [0x3f, 0x02, 0x15, b'A', b'B'];
+        assert_eq!(
+            parse(&report, PosReportFormat::LengthFirst, true),
+            (None, b"AB".as_slice(), true)
+        );
+    }
+
+    #[test]
+    fn rejects_short_reports() {
+        for format in enum_iterator::all::<PosReportFormat>() {
+            assert!(
+                ScannedDataReport::parse(&[], format, true).is_err(),
+                "{format}"
+            );
+            assert!(
+                ScannedDataReport::parse(&[0x00], format, true).is_err(),
+                "{format}"
+            );
+        }
+
+        assert!(ScannedDataReport::parse(
+            &[0x01, b'E', b'0', b'0'],
+            PosReportFormat::Standard,
+            true
+        )
+        .is_err());
+        assert!(ScannedDataReport::parse(
+            &[0x02, 0x01, b'E', b'0', b'0'],
+            PosReportFormat::StandardWithReportId,
+            true
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn handles_zero_length_reports() {
+        assert_eq!(PosReportFormat::detect(&[]), PosReportFormat::Standard);
+
+        let report = standard_report(b"]E0", b"", false);
+        assert_eq!(
+            parse(&report, PosReportFormat::Auto, true),
+            (Some((b'E', b'0')), b"".as_slice(), false)
+        );
+
+        let report = length_first_report(b"");
+        assert_eq!(
+            parse(&report, PosReportFormat::LengthFirst, true),
+            (None, b"".as_slice(), false)
+        );
+    }
+}
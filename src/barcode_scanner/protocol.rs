@@ -0,0 +1,392 @@
+use enum_iterator::Sequence;
+use serde::{Deserialize, Serialize};
+
+/// A command that can be sent to a scanner.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ScannerCommand {
+    /// Start a scan, as if the trigger was pressed.
+    Trigger,
+    /// Indicate that the last scan was successfully decoded.
+    GoodRead,
+    /// Indicate that the last scan could not be decoded.
+    BadRead,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Sequence, Serialize, Deserialize)]
+pub(crate) enum SerialProtocolType {
+    #[default]
+    Raw,
+    ZebraSsi,
+    HoneywellAckNak,
+}
+
+impl std::fmt::Display for SerialProtocolType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Raw => write!(f, "Raw"),
+            Self::ZebraSsi => write!(f, "Zebra SSI"),
+            Self::HoneywellAckNak => write!(f, "Honeywell ACK/NAK"),
+        }
+    }
+}
+
+impl SerialProtocolType {
+    pub(crate) fn create(&self) -> Box<dyn SerialProtocol> {
+        match self {
+            Self::Raw => Box::new(RawProtocol),
+            Self::ZebraSsi => Box::<ZebraSsiProtocol>::default(),
+            Self::HoneywellAckNak => Box::new(HoneywellAckNakProtocol),
+        }
+    }
+
+    /// If the protocol marks where each message ends, so no other framing is
+    /// needed.
+    pub(crate) fn frames_messages(&self) -> bool {
+        matches!(self, Self::ZebraSsi)
+    }
+
+    /// If the protocol is able to send any commands to the scanner.
+    pub(crate) fn supports_commands(&self) -> bool {
+        !matches!(self, Self::Raw)
+    }
+}
+
+/// The result of processing data read from a scanner.
+#[derive(Debug, Default)]
+pub(crate) struct ProtocolOutput {
+    /// Scan data to be passed on to framing.
+    pub(crate) data: Vec<u8>,
+    /// Bytes that must be written back to the scanner, such as an ACK.
+    pub(crate) reply: Vec<u8>,
+    /// If the data completed a message.
+    pub(crate) end_of_message: bool,
+}
+
+/// A protocol layered on top of a serial connection to a scanner.
+pub(crate) trait SerialProtocol {
+    /// Process data read from the scanner.
+    fn receive(&mut self, data: &[u8]) -> ProtocolOutput;
+
+    /// Encode a command to be written to the scanner, if it is supported.
+    fn command(&self, command: ScannerCommand) -> Option<Vec<u8>>;
+}
+
+/// Data is passed through unchanged and no commands are supported.
+struct RawProtocol;
+
+impl SerialProtocol for RawProtocol {
+    fn receive(&mut self, data: &[u8]) -> ProtocolOutput {
+        ProtocolOutput {
+            data: data.to_vec(),
+            ..Default::default()
+        }
+    }
+
+    fn command(&self, _command: ScannerCommand) -> Option<Vec<u8>> {
+        None
+    }
+}
+
+/// Honeywell scanners in ACK/NAK mode wait for the host to acknowledge each
+/// scan, sounding a good read beep on ACK and an error beep on NAK.
+struct HoneywellAckNakProtocol;
+
+impl HoneywellAckNakProtocol {
+    const SYN: u8 = 0x16;
+    const ACK: u8 = 0x06;
+    const NAK: u8 = 0x15;
+}
+
+impl SerialProtocol for HoneywellAckNakProtocol {
+    fn receive(&mut self, data: &[u8]) -> ProtocolOutput {
+        ProtocolOutput {
+            data: data.to_vec(),
+            ..Default::default()
+        }
+    }
+
+    fn command(&self, command: ScannerCommand) -> Option<Vec<u8>> {
+        let data = match command {
+            ScannerCommand::Trigger => vec![Self::SYN, b'T', b'\r'],
+            ScannerCommand::GoodRead => vec![Self::ACK],
+            ScannerCommand::BadRead => vec![Self::NAK],
+        };
+
+        Some(data)
+    }
+}
+
+/// Zebra's Simple Serial Interface, where all data is sent in checksummed
+/// packets that must be acknowledged.
+#[derive(Debug, Default)]
+struct ZebraSsiProtocol {
+    buf: Vec<u8>,
+    message_started: bool,
+}
+
+impl ZebraSsiProtocol {
+    const CMD_ACK: u8 = 0xd0;
+    const CMD_NAK: u8 = 0xd1;
+    const DECODE_DATA: u8 = 0xf3;
+    const START_SESSION: u8 = 0xe4;
+    const BEEP: u8 = 0xe6;
+
+    const SOURCE_HOST: u8 = 0x04;
+
+    const STATUS_CONTINUATION: u8 = 0b0000_0010;
+
+    const NAK_RESEND: u8 = 0x01;
+
+    /// One short high tone.
+    const GOOD_READ_BEEP: u8 = 0x00;
+    /// Two short low tones.
+    const BAD_READ_BEEP: u8 = 0x06;
+
+    /// Length, opcode, source, and status.
+    const HEADER_LEN: usize = 4;
+    const CHECKSUM_LEN: usize = 2;
+
+    fn checksum(data: &[u8]) -> u16 {
+        let sum = data
+            .iter()
+            .fold(0u16, |sum, byte| sum.wrapping_add(*byte as u16));
+
+        (!sum).wrapping_add(1)
+    }
+
+    fn packet(opcode: u8, data: &[u8]) -> Vec<u8> {
+        let mut packet = Vec::with_capacity(Self::HEADER_LEN + data.len() + Self::CHECKSUM_LEN);
+        packet.push((Self::HEADER_LEN + data.len()) as u8);
+        packet.push(opcode);
+        packet.push(Self::SOURCE_HOST);
+        packet.push(0x00);
+        packet.extend_from_slice(data);
+
+        let checksum = Self::checksum(&packet);
+        packet.extend_from_slice(&checksum.to_be_bytes());
+
+        packet
+    }
+
+    /// Convert an SSI code type into an AIM code character and modifier.
+    fn aim_identifier(code_type: u8) -> Option<(u8, u8)> {
+        let identifier = match code_type {
+            0x01 => (b'A', b'0'),
+            0x03 => (b'C', b'0'),
+            0x08 | 0x0a | 0x0b => (b'E', b'0'),
+            0x0f => (b'C', b'1'),
+            0x11 => (b'L', b'0'),
+            0x1b => (b'd', b'1'),
+            0x1c => (b'Q', b'1'),
+            0x2d => (b'z', b'0'),
+            _ => return None,
+        };
+
+        Some(identifier)
+    }
+
+    fn handle_packet(&mut self, packet: &[u8], output: &mut ProtocolOutput) {
+        let opcode = packet[1];
+        let status = packet[3];
+        let data = &packet[Self::HEADER_LEN..];
+
+        match opcode {
+            Self::CMD_ACK => tracing::debug!("scanner acknowledged command"),
+            Self::CMD_NAK => tracing::warn!(reason = data.first(), "scanner rejected command"),
+            Self::DECODE_DATA if !data.is_empty() => {
+                if !self.message_started {
+                    if let Some((code, modifier)) = Self::aim_identifier(data[0]) {
+                        output.data.extend_from_slice(&[b']', code, modifier]);
+                    }
+                }
+
+                output.data.extend_from_slice(&data[1..]);
+
+                self.message_started = status & Self::STATUS_CONTINUATION != 0;
+                output.end_of_message = !self.message_started;
+
+                output.reply.extend(Self::packet(Self::CMD_ACK, &[]));
+            }
+            _ => {
+                tracing::debug!(opcode, "got unhandled packet");
+                output.reply.extend(Self::packet(Self::CMD_ACK, &[]));
+            }
+        }
+    }
+}
+
+impl SerialProtocol for ZebraSsiProtocol {
+    fn receive(&mut self, data: &[u8]) -> ProtocolOutput {
+        let mut output = ProtocolOutput::default();
+
+        self.buf.extend_from_slice(data);
+
+        while let Some(len) = self.buf.first().map(|len| *len as usize) {
+            if len < Self::HEADER_LEN {
+                tracing::warn!(len, "got invalid packet length, discarding buffer");
+                self.buf.clear();
+                break;
+            }
+
+            if self.buf.len() < len + Self::CHECKSUM_LEN {
+                break;
+            }
+
+            let packet: Vec<_> = self.buf.drain(0..len + Self::CHECKSUM_LEN).collect();
+            let (packet, checksum) = packet.split_at(len);
+
+            if Self::checksum(packet).to_be_bytes() != checksum {
+                tracing::warn!(packet = hex::encode(packet), "got packet with bad checksum");
+                output
+                    .reply
+                    .extend(Self::packet(Self::CMD_NAK, &[Self::NAK_RESEND]));
+                continue;
+            }
+
+            tracing::trace!(packet = hex::encode(packet), "got packet");
+            self.handle_packet(packet, &mut output);
+        }
+
+        output
+    }
+
+    fn command(&self, command: ScannerCommand) -> Option<Vec<u8>> {
+        let packet = match command {
+            ScannerCommand::Trigger => Self::packet(Self::START_SESSION, &[]),
+            ScannerCommand::GoodRead => Self::packet(Self::BEEP, &[Self::GOOD_READ_BEEP]),
+            ScannerCommand::BadRead => Self::packet(Self::BEEP, &[Self::BAD_READ_BEEP]),
+        };
+
+        Some(packet)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Decode data for the Code 128 barcode `12345`, with the checksum
+    /// calculated by hand.
+    const DECODE_PACKET: &[u8] = &[
+        0x0a, 0xf3, 0x00, 0x00, 0x03, b'1', b'2', b'3', b'4', b'5', 0xfe, 0x01,
+    ];
+
+    const HOST_ACK: &[u8] = &[0x04, 0xd0, 0x04, 0x00, 0xff, 0x28];
+
+    #[test]
+    fn encodes_ssi_commands() {
+        let protocol = ZebraSsiProtocol::default();
+
+        assert_eq!(
+            protocol.command(ScannerCommand::Trigger).unwrap(),
+            [0x04, 0xe4, 0x04, 0x00, 0xff, 0x14]
+        );
+        assert_eq!(
+            protocol.command(ScannerCommand::GoodRead).unwrap(),
+            [0x05, 0xe6, 0x04, 0x00, 0x00, 0xff, 0x11]
+        );
+        assert_eq!(
+            protocol.command(ScannerCommand::BadRead).unwrap(),
+            [0x05, 0xe6, 0x04, 0x00, 0x06, 0xff, 0x0b]
+        );
+    }
+
+    #[test]
+    fn receives_ssi_decode_data() {
+        let mut protocol = ZebraSsiProtocol::default();
+        let output = protocol.receive(DECODE_PACKET);
+
+        assert_eq!(output.data, b"]C012345");
+        assert_eq!(output.reply, HOST_ACK);
+        assert!(output.end_of_message);
+    }
+
+    #[test]
+    fn buffers_partial_ssi_packets() {
+        let mut protocol = ZebraSsiProtocol::default();
+
+        for chunk in [&DECODE_PACKET[..1], &DECODE_PACKET[1..7]] {
+            let output = protocol.receive(chunk);
+            assert!(output.data.is_empty());
+            assert!(output.reply.is_empty());
+            assert!(!output.end_of_message);
+        }
+
+        let output = protocol.receive(&DECODE_PACKET[7..]);
+        assert_eq!(output.data, b"]C012345");
+        assert_eq!(output.reply, HOST_ACK);
+    }
+
+    #[test]
+    fn receives_multiple_ssi_packets_at_once() {
+        let mut protocol = ZebraSsiProtocol::default();
+        let scanner_ack = [0x04, 0xd0, 0x00, 0x00, 0xff, 0x2c];
+
+        let output = protocol.receive(&[&scanner_ack[..], DECODE_PACKET].concat());
+
+        assert_eq!(output.data, b"]C012345");
+        // Only the decode data needs to be acknowledged.
+        assert_eq!(output.reply, HOST_ACK);
+    }
+
+    /// Build a packet sent from the scanner.
+    fn scanner_packet(opcode: u8, status: u8, data: &[u8]) -> Vec<u8> {
+        let mut packet = vec![(4 + data.len()) as u8, opcode, 0x00, status];
+        packet.extend_from_slice(data);
+        packet.extend(ZebraSsiProtocol::checksum(&packet).to_be_bytes());
+        packet
+    }
+
+    #[test]
+    fn combines_continued_ssi_packets() {
+        let mut protocol = ZebraSsiProtocol::default();
+
+        let first = scanner_packet(0xf3, ZebraSsiProtocol::STATUS_CONTINUATION, b"\x03ab");
+        let output = protocol.receive(&first);
+        assert_eq!(output.data, b"]C0ab");
+        assert!(!output.end_of_message);
+
+        let output = protocol.receive(DECODE_PACKET);
+        assert_eq!(output.data, b"12345");
+        assert!(output.end_of_message);
+    }
+
+    #[test]
+    fn rejects_bad_ssi_checksum() {
+        let mut protocol = ZebraSsiProtocol::default();
+        let mut packet = DECODE_PACKET.to_vec();
+        packet[11] ^= 0xff;
+
+        let output = protocol.receive(&packet);
+
+        assert!(output.data.is_empty());
+        assert_eq!(output.reply, [0x05, 0xd1, 0x04, 0x00, 0x01, 0xff, 0x25]);
+
+        // The next packet is still read.
+        let output = protocol.receive(DECODE_PACKET);
+        assert_eq!(output.data, b"]C012345");
+    }
+
+    #[test]
+    fn discards_invalid_ssi_length() {
+        let mut protocol = ZebraSsiProtocol::default();
+
+        let output = protocol.receive(&[0x02, 0xf3, 0x00]);
+        assert!(output.data.is_empty());
+        assert!(output.reply.is_empty());
+        assert!(protocol.buf.is_empty());
+    }
+
+    #[test]
+    fn honeywell_passes_data_through() {
+        let mut protocol = HoneywellAckNakProtocol;
+
+        assert_eq!(protocol.receive(b"12345\r").data, b"12345\r");
+        assert_eq!(protocol.command(ScannerCommand::GoodRead).unwrap(), [0x06]);
+        assert_eq!(protocol.command(ScannerCommand::BadRead).unwrap(), [0x15]);
+        assert_eq!(
+            protocol.command(ScannerCommand::Trigger).unwrap(),
+            [0x16, b'T', b'\r']
+        );
+    }
+}
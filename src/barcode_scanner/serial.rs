@@ -0,0 +1,160 @@
+use enum_iterator::Sequence;
+use serde::{Deserialize, Serialize};
+
+use super::SerialProtocolType;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Sequence, Serialize, Deserialize)]
+pub(crate) enum DataBits {
+    Five,
+    Six,
+    Seven,
+    #[default]
+    Eight,
+}
+
+impl std::fmt::Display for DataBits {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Five => write!(f, "5"),
+            Self::Six => write!(f, "6"),
+            Self::Seven => write!(f, "7"),
+            Self::Eight => write!(f, "8"),
+        }
+    }
+}
+
+impl From<DataBits> for tokio_serial::DataBits {
+    fn from(value: DataBits) -> Self {
+        match value {
+            DataBits::Five => Self::Five,
+            DataBits::Six => Self::Six,
+            DataBits::Seven => Self::Seven,
+            DataBits::Eight => Self::Eight,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Sequence, Serialize, Deserialize)]
+pub(crate) enum Parity {
+    #[default]
+    None,
+    Odd,
+    Even,
+}
+
+impl std::fmt::Display for Parity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::None => write!(f, "None"),
+            Self::Odd => write!(f, "Odd"),
+            Self::Even => write!(f, "Even"),
+        }
+    }
+}
+
+impl From<Parity> for tokio_serial::Parity {
+    fn from(value: Parity) -> Self {
+        match value {
+            Parity::None => Self::None,
+            Parity::Odd => Self::Odd,
+            Parity::Even => Self::Even,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Sequence, Serialize, Deserialize)]
+pub(crate) enum StopBits {
+    #[default]
+    One,
+    Two,
+}
+
+impl std::fmt::Display for StopBits {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::One => write!(f, "1"),
+            Self::Two => write!(f, "2"),
+        }
+    }
+}
+
+impl From<StopBits> for tokio_serial::StopBits {
+    fn from(value: StopBits) -> Self {
+        match value {
+            StopBits::One => Self::One,
+            StopBits::Two => Self::Two,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Sequence, Serialize, Deserialize)]
+pub(crate) enum FlowControl {
+    #[default]
+    None,
+    Software,
+    Hardware,
+}
+
+impl std::fmt::Display for FlowControl {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::None => write!(f, "None"),
+            Self::Software => write!(f, "XON/XOFF"),
+            Self::Hardware => write!(f, "RTS/CTS"),
+        }
+    }
+}
+
+impl From<FlowControl> for tokio_serial::FlowControl {
+    fn from(value: FlowControl) -> Self {
+        match value {
+            FlowControl::None => Self::None,
+            FlowControl::Software => Self::Software,
+            FlowControl::Hardware => Self::Hardware,
+        }
+    }
+}
+
+/// Settings for opening a serial port.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct SerialConfig {
+    pub(crate) baud_rate: u32,
+    pub(crate) data_bits: DataBits,
+    pub(crate) parity: Parity,
+    pub(crate) stop_bits: StopBits,
+    pub(crate) flow_control: FlowControl,
+    /// The state to set DTR to after opening, or `None` to leave it as is.
+    pub(crate) dtr: Option<bool>,
+    /// The state to set RTS to after opening, or `None` to leave it as is.
+    pub(crate) rts: Option<bool>,
+    pub(crate) protocol: SerialProtocolType,
+}
+
+impl Default for SerialConfig {
+    fn default() -> Self {
+        Self {
+            baud_rate: 9600,
+            data_bits: Default::default(),
+            parity: Default::default(),
+            stop_bits: Default::default(),
+            flow_control: Default::default(),
+            dtr: None,
+            rts: None,
+            protocol: Default::default(),
+        }
+    }
+}
+
+impl SerialConfig {
+    pub(crate) const COMMON_BAUD_RATES: &'static [u32] =
+        &[1200, 2400, 4800, 9600, 19_200, 38_400, 57_600, 115_200];
+
+    pub(crate) fn builder(&self, path: &str) -> tokio_serial::SerialPortBuilder {
+        tokio_serial::new(path, self.baud_rate)
+            .data_bits(self.data_bits.into())
+            .parity(self.parity.into())
+            .stop_bits(self.stop_bits.into())
+            .flow_control(self.flow_control.into())
+    }
+}
@@ -0,0 +1,214 @@
+use async_trait::async_trait;
+use eframe::egui::{TextEdit, Ui};
+use futures::future::LocalBoxFuture;
+use tokio::sync::mpsc::{Receiver, Sender, UnboundedReceiver};
+use tokio_util::sync::CancellationToken;
+
+use super::{Device, DeviceType, Scan, ScannerCommand, ScannerOptions};
+
+/// Read from a scanner until cancelled, passing data through a
+/// [`Framer`](super::framing::Framer).
+///
+/// The handler is given the result of the read future and a buffer to append
+/// decoded data to. It returns true if the data marked the end of a message.
+macro_rules! framed_input {
+    ($token:expr, $tx:expr, $framing:expr, $fut:expr, $handler:expr) => {
+        let mut framer = $crate::barcode_scanner::framing::Framer::new($framing);
+
+        let idle_timeout = framer.idle_timeout();
+        let mut interval =
+            tokio::time::interval(idle_timeout.unwrap_or(std::time::Duration::from_secs(1)));
+
+        loop {
+            let scans: Vec<$crate::barcode_scanner::Scan> = tokio::select! {
+                _ = interval.tick(), if idle_timeout.is_some() => {
+                    framer.flush().into_iter().collect()
+                }
+                _ = $tx.closed() => {
+                    tracing::info!("receiver closed, ending task");
+                    break;
+                }
+                _ = $token.cancelled() => {
+                    tracing::info!("task cancelled, ending");
+                    break;
+                }
+                res = $fut => {
+                    let data = res?;
+
+                    let mut value = Vec::new();
+                    let end_of_message = ($handler)(data, &mut value);
+
+                    let mut scans = framer.push(&value);
+                    if end_of_message {
+                        scans.extend(framer.flush());
+                    }
+
+                    interval.reset();
+
+                    scans
+                }
+            };
+
+            if let Err(err) = $crate::barcode_scanner::sources::send_scans(&$tx, scans).await {
+                tracing::error!("could not send barcode value: {err}");
+                break;
+            }
+        }
+    };
+}
+
+#[cfg(target_os = "linux")]
+mod evdev;
+mod hid;
+mod network;
+mod replay;
+mod serial;
+mod virtual_device;
+
+/// Everything needed by a running source.
+pub(crate) struct SourceContext {
+    pub(crate) token: CancellationToken,
+    pub(crate) tx: Sender<eyre::Result<Scan>>,
+    /// Commands for the scanner, which may be ignored by sources that don't
+    /// support them.
+    pub(crate) commands: UnboundedReceiver<ScannerCommand>,
+}
+
+/// A kind of scanner that can be discovered and read from.
+#[async_trait]
+pub(crate) trait ScannerSource: Send + Sync {
+    /// The kind of devices from this source, used in messages.
+    fn name(&self) -> &'static str;
+
+    /// If this source is responsible for the device.
+    fn handles(&self, device_type: &DeviceType) -> bool;
+
+    /// Find all devices currently available from this source.
+    async fn enumerate(&self) -> eyre::Result<Vec<Device>>;
+
+    /// Read scans from the device until the context's token is cancelled.
+    ///
+    /// The future is run on a `LocalSet` so it may use types that are !Send.
+    fn run(
+        &self,
+        ctx: SourceContext,
+        device_type: DeviceType,
+        options: ScannerOptions,
+    ) -> LocalBoxFuture<'static, eyre::Result<()>>;
+
+    /// The options to use for a device that hasn't been configured yet.
+    fn default_options(&self, _device: &Device) -> ScannerOptions {
+        Default::default()
+    }
+
+    /// If the device can be connected to with these options.
+    fn can_connect(&self, _options: &ScannerOptions) -> bool {
+        true
+    }
+
+    /// If the device accepts commands with these options.
+    fn supports_commands(&self, _options: &ScannerOptions) -> bool {
+        false
+    }
+
+    /// Render rows of a settings grid for options specific to this source.
+    fn settings(&self, _device: &Device, _options: &mut ScannerOptions, _ui: &mut Ui) {}
+}
+
+static SOURCES: &[&dyn ScannerSource] = &[
+    &hid::HidSource,
+    &serial::SerialSource,
+    &network::NetworkSource,
+    &replay::ReplaySource,
+    &virtual_device::VirtualSource,
+    #[cfg(target_os = "linux")]
+    &evdev::EvdevSource,
+];
+
+/// All available scanner sources.
+pub(crate) fn all() -> &'static [&'static dyn ScannerSource] {
+    SOURCES
+}
+
+/// Find the source responsible for a device.
+pub(crate) fn for_device(device_type: &DeviceType) -> eyre::Result<&'static dyn ScannerSource> {
+    SOURCES
+        .iter()
+        .copied()
+        .find(|source| source.handles(device_type))
+        .ok_or_else(|| eyre::eyre!("no source for device: {device_type:?}"))
+}
+
+/// Render a settings row for capturing raw data to a file.
+fn capture_settings(options: &mut ScannerOptions, ui: &mut Ui) {
+    ui.label("Capture Raw Data");
+    ui.horizontal(|ui| {
+        let mut enabled = options.capture_path.is_some();
+        if ui.checkbox(&mut enabled, "").changed() {
+            options.capture_path = enabled.then(|| "capture.jsonl".to_string());
+        }
+
+        if let Some(capture_path) = options.capture_path.as_mut() {
+            ui.add(TextEdit::singleline(capture_path).desired_width(120.0));
+        }
+    });
+    ui.end_row();
+}
+
+async fn send_scans(
+    tx: &Sender<eyre::Result<Scan>>,
+    scans: Vec<Scan>,
+) -> Result<(), tokio::sync::mpsc::error::SendError<eyre::Result<Scan>>> {
+    for scan in scans {
+        tx.send(Ok(scan)).await?;
+    }
+
+    Ok(())
+}
+
+/// Start reading from a device using its source, returning a channel of its
+/// scans.
+pub(crate) fn start(
+    token: CancellationToken,
+    device_type: DeviceType,
+    options: ScannerOptions,
+    commands: UnboundedReceiver<ScannerCommand>,
+) -> eyre::Result<Receiver<eyre::Result<Scan>>> {
+    let source = for_device(&device_type)?;
+    let (tx, rx) = tokio::sync::mpsc::channel(1);
+
+    eyre::ensure!(
+        source.can_connect(&options),
+        "device could not be connected with current settings"
+    );
+
+    tracing::info!(?device_type, "attempting to connect to device");
+
+    // async-hid has types that are !Send so they need to be spawned locally.
+    // However, there's no easy way to use a `LocalSet` without spawning a
+    // blocking task to wait on that set, so all of that is managed here.
+    tokio::task::spawn_blocking(move || {
+        let rt = tokio::runtime::Handle::current();
+
+        rt.block_on(async {
+            let local = tokio::task::LocalSet::new();
+
+            let err_tx = tx.clone();
+
+            let ctx = SourceContext {
+                token,
+                tx,
+                commands,
+            };
+
+            if let Err(err) = local.run_until(source.run(ctx, device_type, options)).await {
+                tracing::error!("scanner encountered error: {err}");
+                if let Err(err) = err_tx.send(Err(err)).await {
+                    tracing::error!("could not send scanner error: {err}");
+                }
+            }
+        })
+    });
+
+    Ok(rx)
+}
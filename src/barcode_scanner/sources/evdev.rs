@@ -0,0 +1,584 @@
+use async_trait::async_trait;
+use eframe::egui::{ComboBox, Ui};
+use evdev::{EventStream, InputEventKind, Key};
+use futures::{future::LocalBoxFuture, FutureExt};
+
+use crate::barcode_scanner::{
+    capture::{CaptureKind, CapturingReader, RawReader},
+    Device, DeviceType, HidType, KeyboardLayout, ScannerOptions,
+};
+
+use super::{ScannerSource, SourceContext};
+
+/// Linux evdev key codes mapped to their HID keyboard usage.
+static HID_USAGES: phf::Map<u16, u8> = phf::phf_map! {
+    1u16 => 0x29,
+    2u16 => 0x1e,
+    3u16 => 0x1f,
+    4u16 => 0x20,
+    5u16 => 0x21,
+    6u16 => 0x22,
+    7u16 => 0x23,
+    8u16 => 0x24,
+    9u16 => 0x25,
+    10u16 => 0x26,
+    11u16 => 0x27,
+    12u16 => 0x2d,
+    13u16 => 0x2e,
+    14u16 => 0x2a,
+    15u16 => 0x2b,
+    16u16 => 0x14,
+    17u16 => 0x1a,
+    18u16 => 0x08,
+    19u16 => 0x15,
+    20u16 => 0x17,
+    21u16 => 0x1c,
+    22u16 => 0x18,
+    23u16 => 0x0c,
+    24u16 => 0x12,
+    25u16 => 0x13,
+    26u16 => 0x2f,
+    27u16 => 0x30,
+    28u16 => 0x28,
+    30u16 => 0x04,
+    31u16 => 0x16,
+    32u16 => 0x07,
+    33u16 => 0x09,
+    34u16 => 0x0a,
+    35u16 => 0x0b,
+    36u16 => 0x0d,
+    37u16 => 0x0e,
+    38u16 => 0x0f,
+    39u16 => 0x33,
+    40u16 => 0x34,
+    41u16 => 0x35,
+    43u16 => 0x31,
+    44u16 => 0x1d,
+    45u16 => 0x1b,
+    46u16 => 0x06,
+    47u16 => 0x19,
+    48u16 => 0x05,
+    49u16 => 0x11,
+    50u16 => 0x10,
+    51u16 => 0x36,
+    52u16 => 0x37,
+    53u16 => 0x38,
+    55u16 => 0x55,
+    57u16 => 0x2c,
+    58u16 => 0x39,
+    71u16 => 0x5f,
+    72u16 => 0x60,
+    73u16 => 0x61,
+    74u16 => 0x56,
+    75u16 => 0x5c,
+    76u16 => 0x5d,
+    77u16 => 0x5e,
+    78u16 => 0x57,
+    79u16 => 0x59,
+    80u16 => 0x5a,
+    81u16 => 0x5b,
+    82u16 => 0x62,
+    83u16 => 0x63,
+    86u16 => 0x64,
+    96u16 => 0x58,
+    98u16 => 0x54,
+    117u16 => 0x67,
+};
+
+/// Linux evdev key codes for modifier keys mapped to their bit in a HID
+/// keyboard report.
+static MODIFIER_BITS: phf::Map<u16, u8> = phf::phf_map! {
+    29u16 => 0b00000001,
+    42u16 => 0b00000010,
+    56u16 => 0b00000100,
+    125u16 => 0b00001000,
+    97u16 => 0b00010000,
+    54u16 => 0b00100000,
+    100u16 => 0b01000000,
+    126u16 => 0b10000000,
+};
+
+/// Keyboard wedge scanners read through Linux input devices.
+///
+/// The device is grabbed so its keystrokes are only seen by this app. Any
+/// keyboard-like input device is listed, including virtual devices created
+/// through uinput.
+pub(super) struct EvdevSource;
+
+#[async_trait]
+impl ScannerSource for EvdevSource {
+    fn name(&self) -> &'static str {
+        "input"
+    }
+
+    fn handles(&self, device_type: &DeviceType) -> bool {
+        matches!(device_type, DeviceType::Evdev { .. })
+    }
+
+    async fn enumerate(&self) -> eyre::Result<Vec<Device>> {
+        let devices = tokio::task::spawn_blocking(|| {
+            evdev::enumerate()
+                .filter(|(_path, device)| is_keyboard(device))
+                .map(|(path, device)| {
+                    let input_id = device.input_id();
+                    let phys = device.physical_path().unwrap_or_default().to_string();
+
+                    Device {
+                        name: format!(
+                            "{} ({}:{} - {})",
+                            device.name().unwrap_or("Unknown Device"),
+                            hex::encode(input_id.vendor().to_be_bytes()),
+                            hex::encode(input_id.product().to_be_bytes()),
+                            path.display()
+                        ),
+                        device_type: DeviceType::Evdev {
+                            vendor_id: input_id.vendor(),
+                            product_id: input_id.product(),
+                            phys,
+                        },
+                    }
+                })
+                .collect()
+        })
+        .await?;
+
+        Ok(devices)
+    }
+
+    fn run(
+        &self,
+        ctx: SourceContext,
+        device_type: DeviceType,
+        options: ScannerOptions,
+    ) -> LocalBoxFuture<'static, eyre::Result<()>> {
+        let DeviceType::Evdev {
+            vendor_id,
+            product_id,
+            phys,
+        } = device_type
+        else {
+            return futures::future::ready(Err(eyre::eyre!("device was not evdev"))).boxed_local();
+        };
+
+        async move {
+            let events = open_evdev_device(vendor_id, product_id, phys).await?;
+            let reader =
+                CapturingReader::new(EvdevReader::new(events), CaptureKind::HidReport, &options)
+                    .await?;
+
+            let options = ScannerOptions {
+                hid_type: HidType::Keyboard,
+                ..options
+            };
+
+            super::hid::read_hid_reports(ctx, reader, options).await
+        }
+        .boxed_local()
+    }
+
+    fn settings(&self, _device: &Device, options: &mut ScannerOptions, ui: &mut Ui) {
+        ui.label("Keyboard Layout");
+        ComboBox::from_label("Keyboard Layout")
+            .selected_text(options.keyboard_layout.to_string())
+            .show_ui(ui, |ui| {
+                ui.style_mut().wrap = Some(false);
+                ui.set_min_width(60.0);
+                for layout in enum_iterator::all::<KeyboardLayout>() {
+                    ui.selectable_value(&mut options.keyboard_layout, layout, layout.to_string());
+                }
+            });
+        ui.end_row();
+
+        super::capture_settings(options, ui);
+    }
+}
+
+fn is_keyboard(device: &evdev::Device) -> bool {
+    device.supported_keys().is_some_and(|keys| {
+        keys.contains(Key::KEY_A) && keys.contains(Key::KEY_1) && keys.contains(Key::KEY_ENTER)
+    })
+}
+
+async fn open_evdev_device(
+    vendor_id: u16,
+    product_id: u16,
+    phys: String,
+) -> eyre::Result<EventStream> {
+    let mut device = tokio::task::spawn_blocking(move || {
+        evdev::enumerate()
+            .map(|(_path, device)| device)
+            .find(|device| {
+                let input_id = device.input_id();
+
+                is_keyboard(device)
+                    && input_id.vendor() == vendor_id
+                    && input_id.product() == product_id
+                    && device.physical_path().unwrap_or_default() == phys
+            })
+    })
+    .await?
+    .ok_or_else(|| {
+        eyre::eyre!(
+            "could not find input device {}:{}",
+            hex::encode(vendor_id.to_be_bytes()),
+            hex::encode(product_id.to_be_bytes())
+        )
+    })?;
+
+    // Prevent keystrokes from also being typed into other applications. The
+    // grab is released when the device is closed.
+    device.grab()?;
+
+    Ok(device.into_event_stream()?)
+}
+
+/// Converts key events into HID boot keyboard input reports, so they can be
+/// handled the same way as keyboard scanners read through HID.
+struct EvdevReader {
+    events: EventStream,
+    keys: KeyState,
+}
+
+impl EvdevReader {
+    fn new(events: EventStream) -> Self {
+        Self {
+            events,
+            keys: Default::default(),
+        }
+    }
+}
+
+/// The modifiers and keys currently held down.
+#[derive(Debug, Default)]
+struct KeyState {
+    modifiers: u8,
+    pressed_keys: Vec<u8>,
+}
+
+impl KeyState {
+    /// Update the pressed keys from an event, returning if anything changed.
+    fn process_key(&mut self, key: Key, value: i32) -> bool {
+        // Repeated keys don't change what is pressed.
+        let pressed = match value {
+            0 => false,
+            1 => true,
+            _ => return false,
+        };
+
+        if let Some(bit) = MODIFIER_BITS.get(&key.code()) {
+            if pressed {
+                self.modifiers |= bit;
+            } else {
+                self.modifiers &= !bit;
+            }
+
+            return true;
+        }
+
+        let Some(usage) = HID_USAGES.get(&key.code()).copied() else {
+            tracing::warn!(code = key.code(), "got unknown key code");
+            return false;
+        };
+
+        if pressed {
+            if self.pressed_keys.len() == 6 {
+                tracing::warn!("too many keys pressed");
+                return false;
+            }
+
+            self.pressed_keys.push(usage);
+        } else {
+            self.pressed_keys.retain(|key| *key != usage);
+        }
+
+        true
+    }
+
+    fn report(&self) -> [u8; 8] {
+        let mut report = [0u8; 8];
+        report[0] = self.modifiers;
+        report[2..2 + self.pressed_keys.len()].copy_from_slice(&self.pressed_keys);
+
+        report
+    }
+}
+
+impl RawReader for EvdevReader {
+    async fn read(&mut self, buf: &mut [u8]) -> eyre::Result<usize> {
+        loop {
+            let event = self.events.next_event().await?;
+
+            let InputEventKind::Key(key) = event.kind() else {
+                continue;
+            };
+
+            if !self.keys.process_key(key, event.value()) {
+                continue;
+            }
+
+            let report = self.keys.report();
+
+            let size = report.len().min(buf.len());
+            buf[0..size].copy_from_slice(&report[0..size]);
+
+            return Ok(size);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use evdev::{
+        uinput::VirtualDeviceBuilder, AttributeSet, BusType, EventType, InputEvent, InputId,
+    };
+    use tokio_util::sync::CancellationToken;
+
+    use crate::barcode_scanner::{
+        framing::{FramingConfig, Terminator},
+        keyboard::KeyboardDecoder,
+    };
+
+    use super::*;
+
+    #[test]
+    fn maps_key_codes_to_usages() {
+        let letters = [
+            Key::KEY_A,
+            Key::KEY_B,
+            Key::KEY_C,
+            Key::KEY_D,
+            Key::KEY_E,
+            Key::KEY_F,
+            Key::KEY_G,
+            Key::KEY_H,
+            Key::KEY_I,
+            Key::KEY_J,
+            Key::KEY_K,
+            Key::KEY_L,
+            Key::KEY_M,
+            Key::KEY_N,
+            Key::KEY_O,
+            Key::KEY_P,
+            Key::KEY_Q,
+            Key::KEY_R,
+            Key::KEY_S,
+            Key::KEY_T,
+            Key::KEY_U,
+            Key::KEY_V,
+            Key::KEY_W,
+            Key::KEY_X,
+            Key::KEY_Y,
+            Key::KEY_Z,
+        ];
+        let digits = [
+            Key::KEY_1,
+            Key::KEY_2,
+            Key::KEY_3,
+            Key::KEY_4,
+            Key::KEY_5,
+            Key::KEY_6,
+            Key::KEY_7,
+            Key::KEY_8,
+            Key::KEY_9,
+            Key::KEY_0,
+        ];
+        let keypad = [
+            Key::KEY_KP1,
+            Key::KEY_KP2,
+            Key::KEY_KP3,
+            Key::KEY_KP4,
+            Key::KEY_KP5,
+            Key::KEY_KP6,
+            Key::KEY_KP7,
+            Key::KEY_KP8,
+            Key::KEY_KP9,
+            Key::KEY_KP0,
+        ];
+
+        for (keys, first_usage) in [(&letters[..], 0x04), (&digits, 0x1e), (&keypad, 0x59)] {
+            for (key, usage) in keys.iter().zip(first_usage..) {
+                assert_eq!(HID_USAGES.get(&key.code()), Some(&usage), "{key:?}");
+            }
+        }
+
+        for (key, usage) in [
+            (Key::KEY_ENTER, 0x28),
+            (Key::KEY_ESC, 0x29),
+            (Key::KEY_BACKSPACE, 0x2a),
+            (Key::KEY_TAB, 0x2b),
+            (Key::KEY_SPACE, 0x2c),
+            (Key::KEY_MINUS, 0x2d),
+            (Key::KEY_RIGHTBRACE, 0x30),
+            (Key::KEY_SEMICOLON, 0x33),
+            (Key::KEY_GRAVE, 0x35),
+            (Key::KEY_SLASH, 0x38),
+            (Key::KEY_CAPSLOCK, 0x39),
+            (Key::KEY_KPENTER, 0x58),
+            (Key::KEY_102ND, 0x64),
+        ] {
+            assert_eq!(HID_USAGES.get(&key.code()), Some(&usage), "{key:?}");
+        }
+
+        for (key, bit) in [
+            (Key::KEY_LEFTCTRL, 0b0000_0001),
+            (Key::KEY_LEFTSHIFT, 0b0000_0010),
+            (Key::KEY_LEFTALT, 0b0000_0100),
+            (Key::KEY_RIGHTSHIFT, 0b0010_0000),
+            (Key::KEY_RIGHTALT, 0b0100_0000),
+        ] {
+            assert_eq!(MODIFIER_BITS.get(&key.code()), Some(&bit), "{key:?}");
+        }
+    }
+
+    #[test]
+    fn builds_reports() {
+        let mut keys = KeyState::default();
+
+        assert!(keys.process_key(Key::KEY_LEFTSHIFT, 1));
+        assert!(keys.process_key(Key::KEY_A, 1));
+        assert_eq!(keys.report(), [0b0000_0010, 0, 0x04, 0, 0, 0, 0, 0]);
+
+        // Repeats and unknown keys don't change anything.
+        assert!(!keys.process_key(Key::KEY_A, 2));
+        assert!(!keys.process_key(Key::KEY_POWER, 1));
+
+        assert!(keys.process_key(Key::KEY_B, 1));
+        assert!(keys.process_key(Key::KEY_A, 0));
+        assert!(keys.process_key(Key::KEY_LEFTSHIFT, 0));
+        assert_eq!(keys.report(), [0, 0, 0x05, 0, 0, 0, 0, 0]);
+
+        // Boot keyboard reports only have room for six keys.
+        for key in [Key::KEY_C, Key::KEY_D, Key::KEY_E, Key::KEY_F, Key::KEY_G] {
+            assert!(keys.process_key(key, 1));
+        }
+        assert!(!keys.process_key(Key::KEY_H, 1));
+        assert_eq!(keys.report(), [0, 0, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a]);
+    }
+
+    /// Key events to press and release each key, holding Shift for the first
+    /// one.
+    fn type_events(text: &[Key]) -> Vec<(Key, i32)> {
+        let mut events = vec![(Key::KEY_LEFTSHIFT, 1)];
+
+        for (index, key) in text.iter().copied().enumerate() {
+            events.push((key, 1));
+            events.push((key, 0));
+
+            if index == 0 {
+                events.push((Key::KEY_LEFTSHIFT, 0));
+            }
+        }
+
+        events
+    }
+
+    const TEXT: [Key; 6] = [
+        Key::KEY_H,
+        Key::KEY_I,
+        Key::KEY_MINUS,
+        Key::KEY_4,
+        Key::KEY_2,
+        Key::KEY_ENTER,
+    ];
+
+    #[test]
+    fn decodes_reports_as_keyboard() {
+        let mut keys = KeyState::default();
+        let mut decoder = KeyboardDecoder::new(KeyboardLayout::Us);
+        let mut value = String::new();
+
+        for (key, state) in type_events(&TEXT) {
+            if keys.process_key(key, state) {
+                decoder.process_report(&keys.report(), &mut value);
+            }
+        }
+
+        assert_eq!(value, "Hi-42\n");
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    #[ignore = "requires write access to /dev/uinput"]
+    async fn reads_from_uinput_device() {
+        const VENDOR_ID: u16 = 0x1209;
+        const PRODUCT_ID: u16 = 0x0001;
+
+        // Only devices with A, 1, and Enter are listed as keyboards.
+        let mut supported_keys = AttributeSet::<Key>::new();
+        for key in TEXT
+            .iter()
+            .chain(&[Key::KEY_A, Key::KEY_1, Key::KEY_LEFTSHIFT])
+        {
+            supported_keys.insert(*key);
+        }
+
+        let mut device = VirtualDeviceBuilder::new()
+            .unwrap()
+            .name("barcode-scanner test keyboard")
+            .input_id(InputId::new(BusType::BUS_USB, VENDOR_ID, PRODUCT_ID, 1))
+            .with_keys(&supported_keys)
+            .unwrap()
+            .build()
+            .unwrap();
+
+        // Give udev a moment to create the device node.
+        tokio::time::sleep(Duration::from_millis(500)).await;
+
+        let phys = EvdevSource
+            .enumerate()
+            .await
+            .unwrap()
+            .into_iter()
+            .find_map(|device| match device.device_type {
+                DeviceType::Evdev {
+                    vendor_id: VENDOR_ID,
+                    product_id: PRODUCT_ID,
+                    phys,
+                } => Some(phys),
+                _ => None,
+            })
+            .expect("virtual device should be listed");
+
+        let options = ScannerOptions {
+            framing: FramingConfig {
+                terminators: vec![Terminator::Lf],
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let token = CancellationToken::new();
+        let (_commands_tx, commands) = tokio::sync::mpsc::unbounded_channel();
+        let mut rx = crate::barcode_scanner::start_scanner(
+            token.clone(),
+            DeviceType::Evdev {
+                vendor_id: VENDOR_ID,
+                product_id: PRODUCT_ID,
+                phys,
+            },
+            options,
+            commands,
+        )
+        .await
+        .unwrap();
+
+        // Wait for the device to be opened and grabbed.
+        tokio::time::sleep(Duration::from_millis(500)).await;
+
+        for (key, state) in type_events(&TEXT) {
+            device
+                .emit(&[InputEvent::new(EventType::KEY, key.code(), state)])
+                .unwrap();
+        }
+
+        let scan = tokio::time::timeout(Duration::from_secs(5), rx.recv())
+            .await
+            .expect("scan should be read")
+            .unwrap()
+            .unwrap();
+        assert_eq!(scan.text(), Some("Hi-42"));
+
+        token.cancel();
+    }
+}
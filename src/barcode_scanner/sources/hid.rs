@@ -0,0 +1,260 @@
+use async_trait::async_trait;
+use eframe::egui::{ComboBox, Ui};
+use futures::{future::LocalBoxFuture, FutureExt, StreamExt};
+use tokio_util::sync::CancellationToken;
+
+use crate::barcode_scanner::{
+    capture::{CaptureKind, CapturingReader, RawReader},
+    keyboard::KeyboardDecoder,
+    pos::ScannedDataReport,
+    Device, DeviceType, HidType, KeyboardLayout, PosReportFormat, Scan, ScannerOptions,
+};
+
+use super::{ScannerSource, SourceContext};
+
+/// USB HID scanners, either acting as a keyboard or as a Point of Sale
+/// barcode scanner.
+pub(super) struct HidSource;
+
+#[async_trait]
+impl ScannerSource for HidSource {
+    fn name(&self) -> &'static str {
+        "HID"
+    }
+
+    fn handles(&self, device_type: &DeviceType) -> bool {
+        matches!(device_type, DeviceType::Hid { .. })
+    }
+
+    async fn enumerate(&self) -> eyre::Result<Vec<Device>> {
+        let devices = async_hid::DeviceInfo::enumerate()
+            .await?
+            .map(|device| Device {
+                name: format!(
+                    "{} ({}:{} - {}, {})",
+                    device.name,
+                    hex::encode(device.vendor_id.to_be_bytes()),
+                    hex::encode(device.product_id.to_be_bytes()),
+                    device.usage_id,
+                    device.usage_page
+                ),
+                device_type: DeviceType::Hid {
+                    usage_page: device.usage_page,
+                    usage_id: device.usage_id,
+                    vendor_id: device.vendor_id,
+                    product_id: device.product_id,
+                },
+            })
+            .collect()
+            .await;
+
+        Ok(devices)
+    }
+
+    fn run(
+        &self,
+        ctx: SourceContext,
+        device_type: DeviceType,
+        options: ScannerOptions,
+    ) -> LocalBoxFuture<'static, eyre::Result<()>> {
+        let DeviceType::Hid {
+            usage_page,
+            usage_id,
+            vendor_id,
+            product_id,
+        } = device_type
+        else {
+            return futures::future::ready(Err(eyre::eyre!("device was not hid"))).boxed_local();
+        };
+
+        async move {
+            let device = get_hid_device(usage_page, usage_id, vendor_id, product_id).await?;
+            let reader = CapturingReader::new(device, CaptureKind::HidReport, &options).await?;
+
+            read_hid_reports(ctx, reader, options).await
+        }
+        .boxed_local()
+    }
+
+    fn default_options(&self, device: &Device) -> ScannerOptions {
+        let hid_type = match device.device_type {
+            DeviceType::Hid { usage_id: 6, .. } => HidType::Keyboard,
+            _ => HidType::Pos,
+        };
+
+        ScannerOptions {
+            hid_type,
+            ..Default::default()
+        }
+    }
+
+    fn settings(&self, _device: &Device, options: &mut ScannerOptions, ui: &mut Ui) {
+        ui.label("HID Type");
+        ComboBox::from_label("HID Type")
+            .selected_text(options.hid_type.to_string())
+            .show_ui(ui, |ui| {
+                ui.style_mut().wrap = Some(false);
+                ui.set_min_width(60.0);
+                for hid_type in enum_iterator::all::<HidType>() {
+                    ui.selectable_value(&mut options.hid_type, hid_type, hid_type.to_string());
+                }
+            });
+        ui.end_row();
+
+        ui.label("Keyboard Layout");
+        ui.add_enabled_ui(options.hid_type == HidType::Keyboard, |ui| {
+            ComboBox::from_label("Keyboard Layout")
+                .selected_text(options.keyboard_layout.to_string())
+                .show_ui(ui, |ui| {
+                    ui.style_mut().wrap = Some(false);
+                    ui.set_min_width(60.0);
+                    for layout in enum_iterator::all::<KeyboardLayout>() {
+                        ui.selectable_value(
+                            &mut options.keyboard_layout,
+                            layout,
+                            layout.to_string(),
+                        );
+                    }
+                });
+        });
+        ui.end_row();
+
+        ui.label("Report Format");
+        ui.add_enabled_ui(options.hid_type == HidType::Pos, |ui| {
+            ComboBox::from_label("Report Format")
+                .selected_text(options.pos_report_format.to_string())
+                .show_ui(ui, |ui| {
+                    ui.style_mut().wrap = Some(false);
+                    ui.set_min_width(60.0);
+                    for format in enum_iterator::all::<PosReportFormat>() {
+                        ui.selectable_value(
+                            &mut options.pos_report_format,
+                            format,
+                            format.to_string(),
+                        );
+                    }
+                });
+        });
+        ui.end_row();
+
+        super::capture_settings(options, ui);
+    }
+}
+
+/// Parse HID input reports from a reader, based on the configured HID type.
+pub(super) async fn read_hid_reports<R: RawReader>(
+    ctx: SourceContext,
+    reader: R,
+    options: ScannerOptions,
+) -> eyre::Result<()> {
+    match options.hid_type {
+        HidType::Keyboard => hid_scanner_keyboard(ctx.token, ctx.tx, reader, options).await,
+        HidType::Pos => hid_scanner_pos(ctx.token, ctx.tx, reader, options).await,
+    }
+}
+
+async fn get_hid_device(
+    usage_page: u16,
+    usage_id: u16,
+    vendor_id: u16,
+    product_id: u16,
+) -> eyre::Result<async_hid::Device> {
+    let device = async_hid::DeviceInfo::enumerate()
+        .await?
+        .filter(|device| {
+            futures::future::ready(device.matches(usage_page, usage_id, vendor_id, product_id))
+        })
+        .next()
+        .await
+        .ok_or_else(|| {
+            eyre::eyre!(
+                "could not find hid device {}:{}",
+                hex::encode(vendor_id.to_be_bytes()),
+                hex::encode(product_id.to_be_bytes())
+            )
+        })?
+        .open(async_hid::AccessMode::Read)
+        .await?;
+
+    Ok(device)
+}
+
+#[tracing::instrument(skip_all)]
+async fn hid_scanner_keyboard<R: RawReader>(
+    token: CancellationToken,
+    tx: tokio::sync::mpsc::Sender<eyre::Result<Scan>>,
+    mut reader: R,
+    options: ScannerOptions,
+) -> eyre::Result<()> {
+    let mut buf = [0u8; 8];
+    let mut decoder = KeyboardDecoder::new(options.keyboard_layout);
+
+    framed_input!(
+        token,
+        tx,
+        options.framing,
+        reader.read(&mut buf),
+        |size, value: &mut Vec<u8>| {
+            tracing::trace!(size, buf = hex::encode(&buf[0..size]), "got input report");
+
+            let mut text = String::new();
+            decoder.process_report(&buf[0..size], &mut text);
+            value.extend_from_slice(text.as_bytes());
+
+            false
+        }
+    );
+
+    Ok(())
+}
+
+#[tracing::instrument(skip_all)]
+async fn hid_scanner_pos<R: RawReader>(
+    token: CancellationToken,
+    tx: tokio::sync::mpsc::Sender<eyre::Result<Scan>>,
+    mut reader: R,
+    options: ScannerOptions,
+) -> eyre::Result<()> {
+    let mut buf = [0u8; 256];
+    let mut message_started = false;
+    let mut format = options.pos_report_format;
+
+    framed_input!(
+        token,
+        tx,
+        options.framing,
+        reader.read(&mut buf),
+        |size, value: &mut Vec<u8>| {
+            tracing::trace!(size, buf = hex::encode(&buf[0..size]), "got input report");
+
+            // Devices don't change formats, so only detect it once.
+            if format == PosReportFormat::Auto && size > 0 {
+                format = PosReportFormat::detect(&buf[0..size]);
+                tracing::debug!(%format, "detected report format");
+            }
+
+            let report = match ScannedDataReport::parse(&buf[0..size], format, !message_started) {
+                Ok(report) => report,
+                Err(err) => {
+                    tracing::warn!("could not parse input report: {err}");
+                    return false;
+                }
+            };
+
+            // Pass the symbology along as an AIM identifier so it is handled
+            // the same way as scanners that include it in the data.
+            if !message_started {
+                if let Some((code, modifier)) = report.aim_identifier() {
+                    value.extend_from_slice(&[b']', code, modifier]);
+                }
+            }
+
+            value.extend_from_slice(report.data());
+
+            message_started = report.continued();
+            !message_started
+        }
+    );
+
+    Ok(())
+}
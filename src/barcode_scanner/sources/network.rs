@@ -0,0 +1,167 @@
+use async_trait::async_trait;
+use futures::{future::LocalBoxFuture, FutureExt};
+use tokio::{
+    io::{AsyncRead, AsyncReadExt},
+    net::{TcpListener, TcpStream, UdpSocket},
+};
+use tokio_util::sync::CancellationToken;
+
+use crate::barcode_scanner::{Device, DeviceType, NetworkMode, Scan, ScannerOptions};
+
+use super::{ScannerSource, SourceContext};
+
+/// Scanners that send data over the network. They can't be discovered, so
+/// they must be added manually.
+pub(super) struct NetworkSource;
+
+#[async_trait]
+impl ScannerSource for NetworkSource {
+    fn name(&self) -> &'static str {
+        "network"
+    }
+
+    fn handles(&self, device_type: &DeviceType) -> bool {
+        matches!(device_type, DeviceType::Network { .. })
+    }
+
+    async fn enumerate(&self) -> eyre::Result<Vec<Device>> {
+        Ok(Vec::new())
+    }
+
+    fn run(
+        &self,
+        ctx: SourceContext,
+        device_type: DeviceType,
+        options: ScannerOptions,
+    ) -> LocalBoxFuture<'static, eyre::Result<()>> {
+        let DeviceType::Network { mode, address } = device_type else {
+            return futures::future::ready(Err(eyre::eyre!("device was not network")))
+                .boxed_local();
+        };
+
+        network_scanner(ctx.token, ctx.tx, mode, address, options).boxed_local()
+    }
+
+    fn can_connect(&self, options: &ScannerOptions) -> bool {
+        options.framing.ends_scans()
+    }
+}
+
+#[tracing::instrument(skip(token, tx))]
+async fn network_scanner(
+    token: CancellationToken,
+    tx: tokio::sync::mpsc::Sender<eyre::Result<Scan>>,
+    mode: NetworkMode,
+    address: String,
+    options: ScannerOptions,
+) -> eyre::Result<()> {
+    match mode {
+        NetworkMode::TcpConnect => {
+            let stream = tokio::select! {
+                _ = token.cancelled() => return Ok(()),
+                stream = TcpStream::connect(&address) => stream?,
+            };
+
+            tracing::info!("connected to scanner");
+
+            read_stream(&token, &tx, stream, &options).await
+        }
+        NetworkMode::TcpListen => {
+            let listener = TcpListener::bind(&address).await?;
+
+            loop {
+                let (stream, addr) = tokio::select! {
+                    _ = token.cancelled() => return Ok(()),
+                    _ = tx.closed() => return Ok(()),
+                    conn = listener.accept() => conn?,
+                };
+
+                tracing::info!(%addr, "scanner connected");
+
+                // Only one scanner is read at a time, and a disconnected
+                // scanner isn't an error because another may connect.
+                match read_stream(&token, &tx, stream, &options).await {
+                    Ok(()) => return Ok(()),
+                    Err(err) => tracing::warn!(%addr, "scanner disconnected: {err}"),
+                }
+            }
+        }
+        NetworkMode::UdpListen => {
+            let socket = UdpSocket::bind(&address).await?;
+
+            let mut buf = [0u8; 65_536];
+
+            framed_input!(
+                token,
+                tx,
+                options.framing.clone(),
+                socket.recv(&mut buf),
+                |size, value: &mut Vec<u8>| {
+                    value.extend_from_slice(&buf[0..size]);
+
+                    true
+                }
+            );
+
+            Ok(())
+        }
+    }
+}
+
+/// Read from a stream until cancelled or the stream is closed, returning an
+/// error if it was closed.
+async fn read_stream<R: AsyncRead + Unpin>(
+    token: &CancellationToken,
+    tx: &tokio::sync::mpsc::Sender<eyre::Result<Scan>>,
+    mut stream: R,
+    options: &ScannerOptions,
+) -> eyre::Result<()> {
+    let mut buf = [0u8; 4096];
+
+    framed_input!(
+        token,
+        tx,
+        options.framing.clone(),
+        async {
+            match stream.read(&mut buf).await {
+                Ok(0) => Err(eyre::eyre!("connection closed")),
+                res => res.map_err(eyre::Report::from),
+            }
+        },
+        |size, value: &mut Vec<u8>| {
+            value.extend_from_slice(&buf[0..size]);
+
+            false
+        }
+    );
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::barcode_scanner::framing::{FramingConfig, Terminator};
+
+    use super::*;
+
+    #[test]
+    fn requires_scans_to_end() {
+        let framing = FramingConfig {
+            idle_timeout_ms: None,
+            ..Default::default()
+        };
+
+        assert!(NetworkSource.can_connect(&ScannerOptions::default()));
+        assert!(!NetworkSource.can_connect(&ScannerOptions {
+            framing: framing.clone(),
+            ..Default::default()
+        }));
+        assert!(NetworkSource.can_connect(&ScannerOptions {
+            framing: FramingConfig {
+                terminators: vec![Terminator::Lf],
+                ..framing
+            },
+            ..Default::default()
+        }));
+    }
+}
@@ -0,0 +1,205 @@
+use async_trait::async_trait;
+use eframe::egui::{DragValue, Ui};
+use futures::{future::LocalBoxFuture, FutureExt};
+
+use crate::barcode_scanner::{
+    capture::{CaptureKind, ReplayReader},
+    Device, DeviceType, ScannerOptions,
+};
+
+use super::{ScannerSource, SourceContext};
+
+/// Replays a capture file through the same parsing as the source it was
+/// captured from.
+pub(super) struct ReplaySource;
+
+#[async_trait]
+impl ScannerSource for ReplaySource {
+    fn name(&self) -> &'static str {
+        "replay"
+    }
+
+    fn handles(&self, device_type: &DeviceType) -> bool {
+        matches!(device_type, DeviceType::Replay { .. })
+    }
+
+    async fn enumerate(&self) -> eyre::Result<Vec<Device>> {
+        Ok(Vec::new())
+    }
+
+    fn run(
+        &self,
+        ctx: SourceContext,
+        device_type: DeviceType,
+        options: ScannerOptions,
+    ) -> LocalBoxFuture<'static, eyre::Result<()>> {
+        let DeviceType::Replay { path } = device_type else {
+            return futures::future::ready(Err(eyre::eyre!("device was not replay"))).boxed_local();
+        };
+
+        async move {
+            let (header, reader) = ReplayReader::open(&path, options.replay_speed).await?;
+            tracing::info!(path, kind = ?header.kind, "replaying capture");
+
+            // Use the options from when the data was captured so it's parsed
+            // exactly the same way.
+            let options = ScannerOptions {
+                capture_path: None,
+                ..header.options
+            };
+
+            match header.kind {
+                CaptureKind::HidReport => super::hid::read_hid_reports(ctx, reader, options).await,
+                CaptureKind::Serial => {
+                    super::serial::read_serial(ctx.token, ctx.tx, reader, None, options).await
+                }
+            }
+        }
+        .boxed_local()
+    }
+
+    fn settings(&self, _device: &Device, options: &mut ScannerOptions, ui: &mut Ui) {
+        ui.label("Replay Speed");
+        ui.add(
+            DragValue::new(&mut options.replay_speed)
+                .clamp_range(0.0..=100.0)
+                .speed(0.1)
+                .custom_formatter(|value, _| {
+                    if value == 0.0 {
+                        "Instant".to_string()
+                    } else {
+                        format!("{value:.1}x")
+                    }
+                }),
+        );
+        ui.end_row();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use tokio_util::sync::CancellationToken;
+
+    use crate::barcode_scanner::Symbology;
+
+    use super::*;
+
+    /// Replay a capture from the fixtures directory, collecting every scan
+    /// until the scanner goes quiet.
+    async fn replay(name: &str) -> Vec<(Option<Symbology>, String)> {
+        let path = format!(
+            "{}/tests/fixtures/captures/{name}",
+            env!("CARGO_MANIFEST_DIR")
+        );
+
+        // Faster than the original, but still slow enough for the gaps
+        // between scans to be longer than the idle timeout.
+        let options = ScannerOptions {
+            replay_speed: 4.0,
+            ..Default::default()
+        };
+
+        let token = CancellationToken::new();
+        let (_commands_tx, commands) = tokio::sync::mpsc::unbounded_channel();
+        let mut rx = crate::barcode_scanner::start_scanner(
+            token.clone(),
+            DeviceType::Replay { path },
+            options,
+            commands,
+        )
+        .await
+        .unwrap();
+
+        let mut scans = Vec::new();
+        while let Ok(Some(scan)) = tokio::time::timeout(Duration::from_millis(500), rx.recv()).await
+        {
+            let scan = scan.unwrap();
+            scans.push((scan.symbology(), scan.text().unwrap().to_string()));
+        }
+
+        token.cancel();
+
+        scans
+    }
+
+    fn scans(expected: &[(Option<Symbology>, &str)]) -> Vec<(Option<Symbology>, String)> {
+        expected
+            .iter()
+            .map(|(symbology, text)| (*symbology, text.to_string()))
+            .collect()
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn replays_hid_keyboard() {
+        assert_eq!(
+            replay("hid-keyboard.jsonl").await,
+            scans(&[
+                (None, "ABC-123"),
+                (Some(Symbology::EanUpc), "4006381333931")
+            ])
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn replays_evdev() {
+        assert_eq!(
+            replay("evdev.jsonl").await,
+            scans(&[
+                (None, "Café Straße"),
+                (
+                    Some(Symbology::Gs1DataMatrix),
+                    "010950600013435210ABC\x1d17201225"
+                ),
+            ])
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn replays_hid_pos() {
+        assert_eq!(
+            replay("hid-pos.jsonl").await,
+            scans(&[
+                (Some(Symbology::EanUpc), "4006381333931"),
+                (
+                    Some(Symbology::QrCode),
+                    "https://example.com/products/0123456789?ref=scanner-test&lang=en-US"
+                ),
+            ])
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn replays_hid_pos_length_first() {
+        assert_eq!(
+            replay("hid-pos-length-first.jsonl").await,
+            scans(&[(
+                None,
+                &format!("CODE128-LENGTH-FIRST-{}", "0123456789".repeat(5))
+            )])
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn replays_serial() {
+        assert_eq!(
+            replay("serial.jsonl").await,
+            scans(&[
+                (Some(Symbology::Code128), "ABC-123"),
+                (Some(Symbology::EanUpc), "4006381333931"),
+            ])
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn replays_serial_ssi() {
+        assert_eq!(
+            replay("serial-ssi.jsonl").await,
+            scans(&[
+                (Some(Symbology::Code128), "12345"),
+                (Some(Symbology::DataMatrix), "DATAMATRIX-CONTINUED"),
+            ])
+        );
+    }
+}
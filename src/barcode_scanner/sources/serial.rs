@@ -0,0 +1,304 @@
+use async_trait::async_trait;
+use eframe::egui::{ComboBox, DragValue, Ui};
+use futures::{future::LocalBoxFuture, FutureExt};
+use tokio::{
+    io::AsyncWriteExt,
+    sync::mpsc::{UnboundedReceiver, UnboundedSender},
+};
+use tokio_serial::{SerialPort, SerialPortBuilderExt};
+use tokio_util::sync::CancellationToken;
+
+use crate::barcode_scanner::{
+    capture::{CaptureKind, CapturingReader, RawReader, StreamReader},
+    protocol::SerialProtocol,
+    DataBits, Device, DeviceType, FlowControl, Parity, Scan, ScannerCommand, ScannerOptions,
+    SerialConfig, SerialProtocolType, StopBits,
+};
+
+use super::{ScannerSource, SourceContext};
+
+/// Scanners connected to a serial port.
+pub(super) struct SerialSource;
+
+#[async_trait]
+impl ScannerSource for SerialSource {
+    fn name(&self) -> &'static str {
+        "serial"
+    }
+
+    fn handles(&self, device_type: &DeviceType) -> bool {
+        matches!(device_type, DeviceType::Serial { .. })
+    }
+
+    async fn enumerate(&self) -> eyre::Result<Vec<Device>> {
+        let devices = tokio::task::spawn_blocking(tokio_serial::available_ports)
+            .await??
+            .into_iter()
+            .map(|port| Device {
+                name: port.port_name.clone(),
+                device_type: DeviceType::Serial {
+                    path: port.port_name,
+                },
+            })
+            .collect();
+
+        Ok(devices)
+    }
+
+    fn run(
+        &self,
+        ctx: SourceContext,
+        device_type: DeviceType,
+        options: ScannerOptions,
+    ) -> LocalBoxFuture<'static, eyre::Result<()>> {
+        let DeviceType::Serial { path } = device_type else {
+            return futures::future::ready(Err(eyre::eyre!("device was not serial"))).boxed_local();
+        };
+
+        serial_scanner(ctx.token, ctx.tx, path, options, ctx.commands).boxed_local()
+    }
+
+    fn can_connect(&self, options: &ScannerOptions) -> bool {
+        options.serial.baud_rate > 0
+            && (options.framing.ends_scans() || options.serial.protocol.frames_messages())
+    }
+
+    fn supports_commands(&self, options: &ScannerOptions) -> bool {
+        options.serial.protocol.supports_commands()
+    }
+
+    fn settings(&self, _device: &Device, options: &mut ScannerOptions, ui: &mut Ui) {
+        let serial = &mut options.serial;
+
+        ui.label("Baud Rate");
+        ui.horizontal(|ui| {
+            ui.add(DragValue::new(&mut serial.baud_rate).clamp_range(0..=4_000_000));
+
+            ComboBox::from_id_source("baud_rate_presets")
+                .selected_text("Presets")
+                .show_ui(ui, |ui| {
+                    ui.style_mut().wrap = Some(false);
+                    ui.set_min_width(60.0);
+                    for rate in SerialConfig::COMMON_BAUD_RATES.iter().copied() {
+                        ui.selectable_value(&mut serial.baud_rate, rate, rate.to_string());
+                    }
+                });
+        });
+        ui.end_row();
+
+        ui.label("Data Bits");
+        ComboBox::from_label("Data Bits")
+            .selected_text(serial.data_bits.to_string())
+            .show_ui(ui, |ui| {
+                for data_bits in enum_iterator::all::<DataBits>() {
+                    ui.selectable_value(&mut serial.data_bits, data_bits, data_bits.to_string());
+                }
+            });
+        ui.end_row();
+
+        ui.label("Parity");
+        ComboBox::from_label("Parity")
+            .selected_text(serial.parity.to_string())
+            .show_ui(ui, |ui| {
+                for parity in enum_iterator::all::<Parity>() {
+                    ui.selectable_value(&mut serial.parity, parity, parity.to_string());
+                }
+            });
+        ui.end_row();
+
+        ui.label("Stop Bits");
+        ComboBox::from_label("Stop Bits")
+            .selected_text(serial.stop_bits.to_string())
+            .show_ui(ui, |ui| {
+                for stop_bits in enum_iterator::all::<StopBits>() {
+                    ui.selectable_value(&mut serial.stop_bits, stop_bits, stop_bits.to_string());
+                }
+            });
+        ui.end_row();
+
+        ui.label("Flow Control");
+        ComboBox::from_label("Flow Control")
+            .selected_text(serial.flow_control.to_string())
+            .show_ui(ui, |ui| {
+                ui.style_mut().wrap = Some(false);
+                for flow_control in enum_iterator::all::<FlowControl>() {
+                    ui.selectable_value(
+                        &mut serial.flow_control,
+                        flow_control,
+                        flow_control.to_string(),
+                    );
+                }
+            });
+        ui.end_row();
+
+        for (name, line) in [("DTR", &mut serial.dtr), ("RTS", &mut serial.rts)] {
+            ui.label(name);
+            ComboBox::from_label(name)
+                .selected_text(line_state_name(*line))
+                .show_ui(ui, |ui| {
+                    for state in [None, Some(true), Some(false)] {
+                        ui.selectable_value(line, state, line_state_name(state));
+                    }
+                });
+            ui.end_row();
+        }
+
+        ui.label("Protocol");
+        ComboBox::from_label("Protocol")
+            .selected_text(serial.protocol.to_string())
+            .show_ui(ui, |ui| {
+                ui.style_mut().wrap = Some(false);
+                for protocol in enum_iterator::all::<SerialProtocolType>() {
+                    ui.selectable_value(&mut serial.protocol, protocol, protocol.to_string());
+                }
+            });
+        ui.end_row();
+
+        super::capture_settings(options, ui);
+    }
+}
+
+fn line_state_name(state: Option<bool>) -> &'static str {
+    match state {
+        None => "Default",
+        Some(true) => "On",
+        Some(false) => "Off",
+    }
+}
+
+#[tracing::instrument(skip(token, tx, commands))]
+async fn serial_scanner(
+    token: CancellationToken,
+    tx: tokio::sync::mpsc::Sender<eyre::Result<Scan>>,
+    path: String,
+    options: ScannerOptions,
+    commands: UnboundedReceiver<ScannerCommand>,
+) -> eyre::Result<()> {
+    let mut port = options.serial.builder(&path).open_native_async()?;
+
+    if let Some(dtr) = options.serial.dtr {
+        port.write_data_terminal_ready(dtr)?;
+    }
+
+    if let Some(rts) = options.serial.rts {
+        port.write_request_to_send(rts)?;
+    }
+
+    let (reader, writer) = tokio::io::split(port);
+
+    // Replies required by the protocol are written alongside any commands, so
+    // the reader never has to wait on the port to become writable.
+    let (reply_tx, reply_rx) = tokio::sync::mpsc::unbounded_channel();
+    tokio::task::spawn_local(serial_writer(
+        token.clone(),
+        writer,
+        options.serial.protocol.create(),
+        commands,
+        reply_rx,
+    ));
+
+    let reader = CapturingReader::new(StreamReader(reader), CaptureKind::Serial, &options).await?;
+
+    read_serial(token, tx, reader, Some(reply_tx), options).await
+}
+
+/// Read serial data through the configured protocol. Replies are discarded if
+/// there is nowhere to send them.
+#[tracing::instrument(skip_all)]
+pub(super) async fn read_serial<R: RawReader>(
+    token: CancellationToken,
+    tx: tokio::sync::mpsc::Sender<eyre::Result<Scan>>,
+    mut reader: R,
+    reply_tx: Option<UnboundedSender<Vec<u8>>>,
+    options: ScannerOptions,
+) -> eyre::Result<()> {
+    let mut protocol = options.serial.protocol.create();
+    let mut buf = [0u8; 4096];
+
+    framed_input!(
+        token,
+        tx,
+        options.framing,
+        reader.read(&mut buf),
+        |size, value: &mut Vec<u8>| {
+            if size == 0 {
+                return false;
+            }
+
+            let output = protocol.receive(&buf[0..size]);
+
+            if let Some(reply_tx) = reply_tx.as_ref().filter(|_| !output.reply.is_empty()) {
+                if let Err(err) = reply_tx.send(output.reply) {
+                    tracing::error!("could not queue reply: {err}");
+                }
+            }
+
+            value.extend(output.data);
+
+            output.end_of_message
+        }
+    );
+
+    Ok(())
+}
+
+async fn serial_writer(
+    token: CancellationToken,
+    mut writer: tokio::io::WriteHalf<tokio_serial::SerialStream>,
+    protocol: Box<dyn SerialProtocol>,
+    mut commands: UnboundedReceiver<ScannerCommand>,
+    mut replies: UnboundedReceiver<Vec<u8>>,
+) {
+    loop {
+        let data = tokio::select! {
+            _ = token.cancelled() => break,
+            Some(command) = commands.recv() => match protocol.command(command) {
+                Some(data) => data,
+                None => {
+                    tracing::debug!(?command, "protocol does not support command");
+                    continue;
+                }
+            },
+            reply = replies.recv() => match reply {
+                Some(data) => data,
+                None => break,
+            },
+        };
+
+        tracing::trace!(data = hex::encode(&data), "writing to scanner");
+
+        if let Err(err) = writer.write_all(&data).await {
+            tracing::error!("could not write to scanner: {err}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::barcode_scanner::framing::FramingConfig;
+
+    use super::*;
+
+    #[test]
+    fn requires_scans_to_end() {
+        let never_ends = ScannerOptions {
+            framing: FramingConfig {
+                idle_timeout_ms: None,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        assert!(SerialSource.can_connect(&ScannerOptions::default()));
+        assert!(!SerialSource.can_connect(&never_ends));
+
+        // SSI packets mark the end of each scan.
+        let mut ssi = never_ends.clone();
+        ssi.serial.protocol = SerialProtocolType::ZebraSsi;
+        assert!(SerialSource.can_connect(&ssi));
+
+        let mut no_baud_rate = ScannerOptions::default();
+        no_baud_rate.serial.baud_rate = 0;
+        assert!(!SerialSource.can_connect(&no_baud_rate));
+    }
+}
@@ -0,0 +1,143 @@
+use async_trait::async_trait;
+use eframe::egui::{ComboBox, DragValue, TextEdit, Ui};
+use futures::{future::LocalBoxFuture, FutureExt};
+
+use crate::barcode_scanner::{
+    symbology::strip_part_header,
+    virtual_device::{VirtualConfig, VirtualMode, VirtualStep},
+    Device, DeviceType, Scan, ScannerOptions, Symbology,
+};
+
+use super::{ScannerSource, SourceContext};
+
+/// A built-in scanner that emits scans without any hardware.
+pub(super) struct VirtualSource;
+
+#[async_trait]
+impl ScannerSource for VirtualSource {
+    fn name(&self) -> &'static str {
+        "virtual"
+    }
+
+    fn handles(&self, device_type: &DeviceType) -> bool {
+        matches!(device_type, DeviceType::Virtual)
+    }
+
+    async fn enumerate(&self) -> eyre::Result<Vec<Device>> {
+        Ok(vec![Device {
+            name: "Virtual Scanner".to_string(),
+            device_type: DeviceType::Virtual,
+        }])
+    }
+
+    fn run(
+        &self,
+        ctx: SourceContext,
+        _device_type: DeviceType,
+        options: ScannerOptions,
+    ) -> LocalBoxFuture<'static, eyre::Result<()>> {
+        virtual_scanner(ctx, options.virtual_device, options.framing.part_counter).boxed_local()
+    }
+
+    fn settings(&self, _device: &Device, options: &mut ScannerOptions, ui: &mut Ui) {
+        let config = &mut options.virtual_device;
+
+        ui.label("Mode");
+        ComboBox::from_label("Mode")
+            .selected_text(config.mode.to_string())
+            .show_ui(ui, |ui| {
+                for mode in enum_iterator::all::<VirtualMode>() {
+                    ui.selectable_value(&mut config.mode, mode, mode.to_string());
+                }
+            });
+        ui.end_row();
+
+        match config.mode {
+            VirtualMode::List => {
+                ui.label("Scans");
+                ui.add(
+                    TextEdit::multiline(&mut config.list)
+                        .hint_text("One scan per line")
+                        .desired_rows(4)
+                        .desired_width(180.0),
+                );
+                ui.end_row();
+            }
+            VirtualMode::Directory => {
+                ui.label("Directory");
+                ui.add(TextEdit::singleline(&mut config.directory).desired_width(180.0));
+                ui.end_row();
+            }
+            VirtualMode::Script => {
+                ui.label("Script");
+                ui.add(
+                    TextEdit::multiline(&mut config.script)
+                        .code_editor()
+                        .hint_text(VirtualConfig::SCRIPT_HINT)
+                        .desired_rows(6)
+                        .desired_width(180.0),
+                );
+                ui.end_row();
+            }
+        }
+
+        ui.label("Interval");
+        ui.add_enabled_ui(config.mode != VirtualMode::Script, |ui| {
+            ui.add(
+                DragValue::new(&mut config.interval_ms)
+                    .clamp_range(0..=60_000)
+                    .suffix(" ms"),
+            );
+        });
+        ui.end_row();
+
+        ui.label("Repeat");
+        ui.checkbox(&mut config.repeat, "");
+        ui.end_row();
+    }
+}
+
+#[tracing::instrument(skip_all)]
+async fn virtual_scanner(
+    ctx: SourceContext,
+    config: VirtualConfig,
+    part_counter: bool,
+) -> eyre::Result<()> {
+    let steps = config.steps().await?;
+
+    loop {
+        for step in steps.iter() {
+            match step {
+                VirtualStep::Scan(data) => {
+                    let (symbology, data) = Symbology::strip_aim_identifier(data);
+                    let (part, data) = strip_part_header(symbology, data, part_counter);
+                    let scan = Scan::from_bytes(data.to_vec())
+                        .with_symbology(symbology)
+                        .with_part(part);
+
+                    if ctx.tx.send(Ok(scan)).await.is_err() {
+                        tracing::info!("receiver closed, ending task");
+                        return Ok(());
+                    }
+                }
+                VirtualStep::Wait(duration) => {
+                    tokio::select! {
+                        _ = ctx.token.cancelled() => return Ok(()),
+                        _ = tokio::time::sleep(*duration) => (),
+                    }
+                }
+                VirtualStep::Error(message) => eyre::bail!("virtual scanner error: {message}"),
+            }
+
+            if ctx.token.is_cancelled() {
+                return Ok(());
+            }
+        }
+
+        if !config.repeat {
+            tracing::info!("virtual scanner finished");
+            ctx.token.cancelled().await;
+            return Ok(());
+        }
+    }
+}
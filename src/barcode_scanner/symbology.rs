@@ -0,0 +1,243 @@
+use serde::{Deserialize, Serialize};
+
+use super::ScanPart;
+
+/// The type of barcode a scan was read from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub(crate) enum Symbology {
+    Code39,
+    Code93,
+    Code128,
+    Gs1128,
+    Codabar,
+    EanUpc,
+    Interleaved2Of5,
+    Msi,
+    Gs1DataBar,
+    Pdf417,
+    DataMatrix,
+    Gs1DataMatrix,
+    QrCode,
+    Gs1QrCode,
+    Aztec,
+    MaxiCode,
+    Other(char),
+}
+
+impl Symbology {
+    /// Look up the symbology for an AIM symbology identifier's code character
+    /// and modifier.
+    pub(crate) fn from_aim(code: char, modifier: char) -> Self {
+        match (code, modifier) {
+            ('A', _) => Self::Code39,
+            ('G', _) => Self::Code93,
+            ('C', '1') => Self::Gs1128,
+            ('C', _) => Self::Code128,
+            ('F', _) => Self::Codabar,
+            ('E', _) => Self::EanUpc,
+            ('I', _) => Self::Interleaved2Of5,
+            ('M', _) => Self::Msi,
+            ('e', _) => Self::Gs1DataBar,
+            ('L', _) => Self::Pdf417,
+            ('d', '2' | '5') => Self::Gs1DataMatrix,
+            ('d', _) => Self::DataMatrix,
+            ('Q', '3' | '4') => Self::Gs1QrCode,
+            ('Q', _) => Self::QrCode,
+            ('z', _) => Self::Aztec,
+            ('U', _) => Self::MaxiCode,
+            (code, _) => Self::Other(code),
+        }
+    }
+
+    /// Split an AIM symbology identifier (`]` followed by a code character
+    /// and a modifier) from the start of the data, if present.
+    pub(crate) fn strip_aim_identifier(data: &[u8]) -> (Option<Self>, &[u8]) {
+        match data {
+            [b']', code, modifier, rest @ ..]
+                if code.is_ascii_alphabetic() && modifier.is_ascii_alphanumeric() =>
+            {
+                (Some(Self::from_aim(*code as char, *modifier as char)), rest)
+            }
+            _ => (None, data),
+        }
+    }
+}
+
+/// Split a multi-part header from the start of data, after any AIM
+/// identifier was removed.
+///
+/// Scanners that forward the parts of a set individually instead of
+/// combining them may include either the QR Code Structured Append header as
+/// the mode indicator byte `0x03`, the sequence byte, and the parity byte, or
+/// a `[n/m]` part counter. The part counter could also be ordinary data, so
+/// it's only used when enabled.
+pub(crate) fn strip_part_header(
+    symbology: Option<Symbology>,
+    data: &[u8],
+    part_counter: bool,
+) -> (Option<ScanPart>, &[u8]) {
+    const STRUCTURED_APPEND: u8 = 0x03;
+
+    if let (
+        Some(Symbology::QrCode | Symbology::Gs1QrCode),
+        [STRUCTURED_APPEND, sequence, parity, rest @ ..],
+    ) = (symbology, data)
+    {
+        // The sequence has the position in the upper 4 bits and the total
+        // minus one in the lower 4 bits.
+        let index = (sequence >> 4) as usize;
+        let total = (sequence & 0x0f) as usize + 1;
+
+        if index < total {
+            let part = ScanPart {
+                group: format!("qr-{parity}-{total}"),
+                index,
+                total: Some(total),
+                per_source: false,
+            };
+
+            return (Some(part), rest);
+        }
+    }
+
+    if let Some((index, total, rest)) = part_counter.then(|| parse_part_counter(data)).flatten() {
+        // Counters don't identify their set, so they can only be combined with
+        // other parts from the same scanner.
+        let part = ScanPart {
+            group: format!("parts-{total}"),
+            index: index - 1,
+            total: Some(total),
+            per_source: true,
+        };
+
+        return (Some(part), rest);
+    }
+
+    (None, data)
+}
+
+/// Parse a one-based `[n/m]` part counter from the start of data.
+fn parse_part_counter(data: &[u8]) -> Option<(usize, usize, &[u8])> {
+    let data = data.strip_prefix(b"[")?;
+    let end = data.iter().position(|b| *b == b']')?;
+
+    let (index, total) = std::str::from_utf8(&data[..end]).ok()?.split_once('/')?;
+    let (index, total): (usize, usize) = (index.parse().ok()?, total.parse().ok()?);
+
+    (index >= 1 && index <= total && total > 1).then_some((index, total, &data[end + 1..]))
+}
+
+impl std::fmt::Display for Symbology {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Code39 => write!(f, "Code 39"),
+            Self::Code93 => write!(f, "Code 93"),
+            Self::Code128 => write!(f, "Code 128"),
+            Self::Gs1128 => write!(f, "GS1-128"),
+            Self::Codabar => write!(f, "Codabar"),
+            Self::EanUpc => write!(f, "EAN/UPC"),
+            Self::Interleaved2Of5 => write!(f, "Interleaved 2 of 5"),
+            Self::Msi => write!(f, "MSI"),
+            Self::Gs1DataBar => write!(f, "GS1 DataBar"),
+            Self::Pdf417 => write!(f, "PDF417"),
+            Self::DataMatrix => write!(f, "Data Matrix"),
+            Self::Gs1DataMatrix => write!(f, "GS1 DataMatrix"),
+            Self::QrCode => write!(f, "QR Code"),
+            Self::Gs1QrCode => write!(f, "GS1 QR Code"),
+            Self::Aztec => write!(f, "Aztec"),
+            Self::MaxiCode => write!(f, "MaxiCode"),
+            Self::Other(code) => write!(f, "Unknown ({code})"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strips_aim_identifier() {
+        assert_eq!(
+            Symbology::strip_aim_identifier(b"]d2010950"),
+            (Some(Symbology::Gs1DataMatrix), &b"010950"[..])
+        );
+        assert_eq!(
+            Symbology::strip_aim_identifier(b"]Q1text"),
+            (Some(Symbology::QrCode), &b"text"[..])
+        );
+        assert_eq!(
+            Symbology::strip_aim_identifier(b"] text"),
+            (None, &b"] text"[..])
+        );
+        assert_eq!(Symbology::strip_aim_identifier(b"]C"), (None, &b"]C"[..]));
+    }
+
+    #[test]
+    fn parses_structured_append_header() {
+        let (part, rest) = strip_part_header(Some(Symbology::QrCode), b"\x03\x12\x07data", false);
+
+        assert_eq!(
+            part,
+            Some(ScanPart {
+                group: "qr-7-3".to_string(),
+                index: 1,
+                total: Some(3),
+                per_source: false,
+            })
+        );
+        assert_eq!(rest, b"data");
+    }
+
+    #[test]
+    fn requires_qr_code_for_structured_append() {
+        for symbology in [None, Some(Symbology::Code128), Some(Symbology::DataMatrix)] {
+            let (part, rest) = strip_part_header(symbology, b"\x03\x12\x07data", false);
+
+            assert_eq!(part, None);
+            assert_eq!(rest, b"\x03\x12\x07data");
+        }
+
+        // The position must be within the set.
+        let (part, _) = strip_part_header(Some(Symbology::QrCode), b"\x03\x32\x07data", false);
+        assert_eq!(part, None);
+    }
+
+    #[test]
+    fn parses_part_counter_when_enabled() {
+        let (part, rest) = strip_part_header(None, b"[2/3]data", true);
+
+        assert_eq!(
+            part,
+            Some(ScanPart {
+                group: "parts-3".to_string(),
+                index: 1,
+                total: Some(3),
+                per_source: true,
+            })
+        );
+        assert_eq!(rest, b"data");
+
+        let (part, rest) = strip_part_header(None, b"[1/2] Lot A", false);
+        assert_eq!(part, None);
+        assert_eq!(rest, b"[1/2] Lot A");
+    }
+
+    #[test]
+    fn rejects_invalid_part_counters() {
+        for data in [
+            &b"[0/3]data"[..],
+            b"[4/3]data",
+            b"[1/1]data",
+            b"[a/b]data",
+            b"[1-3]data",
+            b"[1/3data",
+            b" [1/3]data",
+            b"data",
+        ] {
+            let (part, rest) = strip_part_header(None, data, true);
+
+            assert_eq!(part, None, "{}", String::from_utf8_lossy(data));
+            assert_eq!(rest, data);
+        }
+    }
+}
@@ -0,0 +1,287 @@
+use std::time::Duration;
+
+use enum_iterator::Sequence;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Sequence, Serialize, Deserialize)]
+pub(crate) enum VirtualMode {
+    /// Emit each line of a list as a scan.
+    #[default]
+    List,
+    /// Emit the contents of each file in a directory as a scan.
+    Directory,
+    /// Run a script of scans, delays, and errors.
+    Script,
+}
+
+impl std::fmt::Display for VirtualMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::List => write!(f, "List"),
+            Self::Directory => write!(f, "Directory"),
+            Self::Script => write!(f, "Script"),
+        }
+    }
+}
+
+/// Settings for the virtual scanner.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct VirtualConfig {
+    pub(crate) mode: VirtualMode,
+    /// Scans for list mode, one per line.
+    pub(crate) list: String,
+    /// The directory of sample files for directory mode.
+    pub(crate) directory: String,
+    /// The script for script mode, see [`VirtualStep::parse_script`].
+    pub(crate) script: String,
+    /// The delay between scans in list and directory modes.
+    pub(crate) interval_ms: u64,
+    /// If the scans should start over once finished.
+    pub(crate) repeat: bool,
+}
+
+impl Default for VirtualConfig {
+    fn default() -> Self {
+        Self {
+            mode: Default::default(),
+            list: String::new(),
+            directory: String::new(),
+            script: String::new(),
+            interval_ms: 1000,
+            repeat: false,
+        }
+    }
+}
+
+impl VirtualConfig {
+    pub(crate) const SCRIPT_HINT: &'static str =
+        "scan <text, \\xHH for bytes>\nhex <bytes>\nwait <milliseconds>\nerror <message>";
+
+    /// Build the steps the virtual scanner should perform.
+    pub(crate) async fn steps(&self) -> eyre::Result<Vec<VirtualStep>> {
+        let interval = VirtualStep::Wait(Duration::from_millis(self.interval_ms));
+
+        let steps = match self.mode {
+            VirtualMode::List => self
+                .list
+                .lines()
+                .filter(|line| !line.is_empty())
+                .flat_map(|line| {
+                    [
+                        VirtualStep::Scan(line.as_bytes().to_vec()),
+                        interval.clone(),
+                    ]
+                })
+                .collect(),
+            VirtualMode::Directory => {
+                let mut paths = Vec::new();
+
+                let mut entries = tokio::fs::read_dir(&self.directory).await?;
+                while let Some(entry) = entries.next_entry().await? {
+                    if entry.file_type().await?.is_file() {
+                        paths.push(entry.path());
+                    }
+                }
+
+                paths.sort();
+
+                let mut steps = Vec::with_capacity(paths.len() * 2);
+                for path in paths {
+                    steps.push(VirtualStep::Scan(tokio::fs::read(path).await?));
+                    steps.push(interval.clone());
+                }
+                steps
+            }
+            VirtualMode::Script => VirtualStep::parse_script(&self.script)?,
+        };
+
+        eyre::ensure!(!steps.is_empty(), "virtual scanner has nothing to scan");
+
+        Ok(steps)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum VirtualStep {
+    Scan(Vec<u8>),
+    Wait(Duration),
+    Error(String),
+}
+
+impl VirtualStep {
+    /// Parse a script, where each line is a command followed by its argument.
+    ///
+    /// * `scan <text>` emits the text as a scan, where `\n`, `\r`, `\t`,
+    ///   `\\`, and `\xHH` are replaced with the bytes they represent
+    /// * `hex <bytes>` emits hex-encoded bytes as a scan
+    /// * `wait <milliseconds>` waits before the next step
+    /// * `error <message>` fails as if the scanner was disconnected
+    ///
+    /// Empty lines and lines starting with `#` are ignored.
+    pub(crate) fn parse_script(script: &str) -> eyre::Result<Vec<Self>> {
+        script
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty() && !line.trim_start().starts_with('#'))
+            .map(|(index, line)| {
+                Self::parse_line(line.trim_start())
+                    .map_err(|err| eyre::eyre!("script line {}: {err}", index + 1))
+            })
+            .collect()
+    }
+
+    fn parse_line(line: &str) -> eyre::Result<Self> {
+        let (command, arg) = line.split_once(' ').unwrap_or((line, ""));
+
+        let step = match command {
+            "scan" => Self::Scan(Self::unescape(arg)?),
+            "hex" => Self::Scan(hex::decode(arg.replace(' ', ""))?),
+            "wait" => Self::Wait(Duration::from_millis(arg.trim().parse()?)),
+            "error" => Self::Error(arg.to_string()),
+            command => eyre::bail!("unknown command {command}"),
+        };
+
+        Ok(step)
+    }
+
+    fn unescape(text: &str) -> eyre::Result<Vec<u8>> {
+        let mut data = Vec::with_capacity(text.len());
+        let mut chars = text.chars();
+
+        while let Some(ch) = chars.next() {
+            if ch != '\\' {
+                data.extend_from_slice(ch.encode_utf8(&mut [0; 4]).as_bytes());
+                continue;
+            }
+
+            let byte = match chars.next() {
+                Some('n') => b'\n',
+                Some('r') => b'\r',
+                Some('t') => b'\t',
+                Some('\\') => b'\\',
+                Some('x') => {
+                    let digits: String = chars.by_ref().take(2).collect();
+                    eyre::ensure!(
+                        digits.len() == 2 && digits.chars().all(|ch| ch.is_ascii_hexdigit()),
+                        "invalid escape \\x{digits}"
+                    );
+
+                    u8::from_str_radix(&digits, 16)?
+                }
+                Some(other) => eyre::bail!("unknown escape \\{other}"),
+                None => eyre::bail!("incomplete escape at end of line"),
+            };
+
+            data.push(byte);
+        }
+
+        Ok(data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_script() {
+        let script = "# a comment\n\nscan hello world\n  wait 250\nhex 01 02ff\nerror unplugged\n";
+
+        assert_eq!(
+            VirtualStep::parse_script(script).unwrap(),
+            [
+                VirtualStep::Scan(b"hello world".to_vec()),
+                VirtualStep::Wait(Duration::from_millis(250)),
+                VirtualStep::Scan(vec![0x01, 0x02, 0xff]),
+                VirtualStep::Error("unplugged".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_delays() {
+        assert_eq!(
+            VirtualStep::parse_script("wait 0\nwait  1500 ").unwrap(),
+            [
+                VirtualStep::Wait(Duration::ZERO),
+                VirtualStep::Wait(Duration::from_millis(1500)),
+            ]
+        );
+
+        for script in ["wait", "wait -1", "wait 1.5", "wait 1s"] {
+            assert!(VirtualStep::parse_script(script).is_err(), "{script}");
+        }
+    }
+
+    #[test]
+    fn parses_escapes() {
+        assert_eq!(
+            VirtualStep::parse_script(r"scan ]d2010950600013435210ABC\x1d17201225\r\n").unwrap(),
+            [VirtualStep::Scan(
+                b"]d2010950600013435210ABC\x1d17201225\r\n".to_vec()
+            )]
+        );
+
+        assert_eq!(
+            VirtualStep::parse_script(r"scan a\tb\\n\xFF").unwrap(),
+            [VirtualStep::Scan(b"a\tb\\n\xff".to_vec())]
+        );
+
+        for script in [
+            r"scan \q",
+            r"scan \x1",
+            r"scan \x+1",
+            r"scan \xzz",
+            r"scan trailing\",
+        ] {
+            assert!(VirtualStep::parse_script(script).is_err(), "{script}");
+        }
+    }
+
+    #[test]
+    fn keeps_empty_arguments() {
+        assert_eq!(
+            VirtualStep::parse_script("scan\nerror").unwrap(),
+            [
+                VirtualStep::Scan(Vec::new()),
+                VirtualStep::Error(String::new())
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_malformed_lines() {
+        let err = VirtualStep::parse_script("scan ok\n\nbeep 3").unwrap_err();
+        assert_eq!(err.to_string(), "script line 3: unknown command beep");
+
+        for script in ["hex 0", "hex zz", "Scan upper"] {
+            assert!(VirtualStep::parse_script(script).is_err(), "{script}");
+        }
+    }
+
+    #[tokio::test]
+    async fn requires_steps() {
+        let config = VirtualConfig {
+            mode: VirtualMode::Script,
+            script: "# nothing to do".to_string(),
+            ..Default::default()
+        };
+        assert!(config.steps().await.is_err());
+
+        let config = VirtualConfig {
+            list: "one\n\ntwo".to_string(),
+            interval_ms: 10,
+            ..Default::default()
+        };
+        assert_eq!(
+            config.steps().await.unwrap(),
+            [
+                VirtualStep::Scan(b"one".to_vec()),
+                VirtualStep::Wait(Duration::from_millis(10)),
+                VirtualStep::Scan(b"two".to_vec()),
+                VirtualStep::Wait(Duration::from_millis(10)),
+            ]
+        );
+    }
+}
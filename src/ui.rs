@@ -1,10 +1,15 @@
 use std::borrow::Cow;
 use std::ops::Not;
-use std::path::PathBuf;
-use std::{collections::VecDeque, fmt::Debug};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use std::{
+    collections::{HashSet, VecDeque},
+    fmt::Debug,
+};
 
 use eframe::egui::{
-    Button, CollapsingHeader, Event, Key, KeyboardShortcut, Modifiers, ScrollArea, SidePanel,
+    Align, Button, CollapsingHeader, Event, Key, KeyboardShortcut, Layout, Modifiers, ScrollArea,
+    SidePanel,
 };
 use eframe::{
     egui::{menu, pos2, vec2, CentralPanel, Rect, TopBottomPanel, Window},
@@ -16,10 +21,18 @@ use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver};
 use tokio_util::sync::CancellationToken;
 
 use crate::barcode_decoders::{self, BoxedBarcodeData};
+use crate::barcode_scanner::{
+    decode_clipboard_image, decode_image, Scan, ScanSource, ScannerCommand, Symbology,
+    IMAGE_EXTENSIONS,
+};
 use crate::config::{ConfigLoader, ConfigLoaderObject};
 
+use self::duplicates::{DuplicateAction, DuplicateFilter, DuplicatePolicy};
+use self::reassembly::Reassembler;
 use self::state_worker::StateWorker;
 
+mod duplicates;
+mod reassembly;
 mod scanner_settings;
 pub mod state_worker;
 
@@ -28,9 +41,16 @@ struct State {
     scanner_settings_open: bool,
     pub scanner_settings: scanner_settings::State,
     decoders: barcode_decoders::BarcodeDecoders,
-    decoded_history: VecDeque<(&'static str, BoxedBarcodeData)>,
+    decoded_history: VecDeque<HistoryEntry>,
     enabled_decoders: Vec<bool>,
-    previous_scan: Option<String>,
+    duplicate_policy: DuplicatePolicy,
+    duplicates: DuplicateFilter,
+    reassembler: Reassembler,
+    /// If text was pasted since the paste shortcut was pressed.
+    pasted_text: bool,
+    /// Keys used for pasting that were seen being pressed. Presses that paste
+    /// aren't reported, so releasing a key that wasn't seen means it pasted.
+    paste_keys_down: HashSet<Key>,
     error: Option<(Cow<'static, str>, String)>,
     decoder_loading: usize,
 }
@@ -38,15 +58,28 @@ struct State {
 impl State {
     fn clear_history(&mut self) {
         self.decoded_history.clear();
-        self.previous_scan = None;
+        self.duplicates.clear();
+        self.reassembler.clear();
     }
 }
 
+#[derive(Debug)]
+struct HistoryEntry {
+    source: ScanSource,
+    decoder_name: &'static str,
+    symbology: Option<Symbology>,
+    data: BoxedBarcodeData,
+    /// If the same data was recently scanned.
+    duplicate: bool,
+}
+
 #[derive(Debug)]
 enum Action {
     Saved,
     ScannerSettings(scanner_settings::Action),
-    GotBarcodeData(Option<(&'static str, BoxedBarcodeData)>),
+    GotBarcodeData(ScanSource, Option<HistoryEntry>),
+    ImagesSelected(Vec<PathBuf>),
+    ScansFound(ScanSource, eyre::Result<Vec<Scan>>),
     DecoderToggled,
     Decoder(barcode_decoders::Action),
 }
@@ -55,6 +88,7 @@ enum Action {
 struct Config {
     scanner: Option<scanner_settings::SavedConfig>,
     disabled_decoders: Option<Vec<String>>,
+    duplicate_policy: Option<DuplicatePolicy>,
 }
 
 struct Application {
@@ -87,6 +121,7 @@ impl ConfigLoaderObject for Application {
                     })
                     .collect(),
             ),
+            duplicate_policy: Some(self.state.duplicate_policy),
         };
 
         serde_json::to_value(config).map_err(Into::into)
@@ -97,6 +132,7 @@ impl ConfigLoaderObject for Application {
         let disabled_decoders = config.disabled_decoders.unwrap_or_default();
 
         self.state.scanner_settings.saved_config = config.scanner;
+        self.state.duplicate_policy = config.duplicate_policy.unwrap_or_default();
         self.state.enabled_decoders = self
             .state
             .decoders
@@ -147,6 +183,114 @@ impl Application {
 }
 
 impl Application {
+    /// Decode the barcodes in an image, reading it from the path if the data
+    /// wasn't already loaded.
+    fn open_image(&mut self, name: String, path: Option<PathBuf>, data: Option<Vec<u8>>) {
+        self.state.decoder_loading += 1;
+
+        self.worker.perform(async move {
+            let source = ScanSource::Image(name);
+
+            let result = async move {
+                let data = match (data, path) {
+                    (Some(data), _) => data,
+                    (None, Some(path)) => tokio::fs::read(path).await?,
+                    (None, None) => eyre::bail!("image had no data"),
+                };
+
+                tokio::task::spawn_blocking(move || decode_image(&data)).await?
+            }
+            .await;
+
+            Action::ScansFound(source, result)
+        });
+    }
+
+    /// Scan the clipboard's text if allowed and present, otherwise decode the
+    /// barcodes in an image on the clipboard.
+    fn scan_clipboard(&mut self, allow_text: bool) {
+        self.state.decoder_loading += 1;
+
+        self.worker.perform(async move {
+            let result = tokio::task::spawn_blocking(move || {
+                if allow_text {
+                    if let Ok(text) = arboard::Clipboard::new()?.get_text() {
+                        if !text.is_empty() {
+                            return Ok(vec![Scan::from_text(text)]);
+                        }
+                    }
+                }
+
+                decode_clipboard_image()
+            })
+            .await
+            .map_err(Into::into)
+            .and_then(|result| result);
+
+            Action::ScansFound(ScanSource::Clipboard, result)
+        });
+    }
+
+    /// Decode a scan, unless it is a duplicate that should be ignored.
+    fn process_scan(&mut self, source: ScanSource, value: Scan) {
+        let duplicate = self
+            .state
+            .duplicates
+            .check(&self.state.duplicate_policy, &source, &value);
+
+        let action = if duplicate {
+            self.state.duplicate_policy.action
+        } else {
+            DuplicateAction::Accept
+        };
+
+        if action == DuplicateAction::Ignore {
+            // The scan was still read, so the scanner shouldn't retry it.
+            self.acknowledge(&source, true);
+            return;
+        }
+
+        let duplicate = action == DuplicateAction::Warn;
+        let decoders = self.state.decoders.clone();
+
+        self.state.decoder_loading += 1;
+
+        self.worker.perform(async move {
+            let symbology = value.symbology();
+            let entry = decoders
+                .decode(&value)
+                .await
+                .map(|(decoder_name, data)| HistoryEntry {
+                    source: source.clone(),
+                    decoder_name,
+                    symbology,
+                    data,
+                    duplicate,
+                });
+
+            Action::GotBarcodeData(source, entry)
+        });
+    }
+
+    /// Tell a scanner if its scan was good, for scanners that indicate it or
+    /// wait for a response before sending more data.
+    fn acknowledge(&mut self, source: &ScanSource, good: bool) {
+        let ScanSource::Device(device) = source else {
+            return;
+        };
+
+        let command = if good {
+            ScannerCommand::GoodRead
+        } else {
+            ScannerCommand::BadRead
+        };
+
+        self.scanner_settings.update(
+            &mut self.state.scanner_settings,
+            scanner_settings::Action::SendCommand(device.clone(), command),
+        );
+    }
+
     fn save_config(&mut self) {
         let config_loader = self.config_loader.clone();
         self.state.scanner_settings.saved_config = Some(self.state.scanner_settings.saved());
@@ -169,22 +313,16 @@ impl App for Application {
                 Action::Saved => (),
                 Action::ScannerSettings(scanner_settings) => {
                     match &scanner_settings {
-                        scanner_settings::Action::ScannedBarcode(Ok(value)) => {
-                            if self.state.previous_scan.as_ref() != Some(value) {
-                                self.state.previous_scan = Some(value.clone());
-
-                                let value = value.clone();
-                                let decoders = self.state.decoders.clone();
-
-                                self.state.decoder_loading += 1;
-
-                                self.worker.perform(async move {
-                                    Action::GotBarcodeData(decoders.decode(&value).await)
-                                });
+                        scanner_settings::Action::ScannedBarcode(source, Ok(value)) => {
+                            match self.state.reassembler.push(source, value.clone()) {
+                                Some(value) => self.process_scan(source.clone(), value),
+                                // Parts are acknowledged as they arrive, as the
+                                // scanner may not send the next until then.
+                                None => self.acknowledge(source, true),
                             }
                         }
-                        scanner_settings::Action::ScannedBarcode(Err(err)) => {
-                            self.state.error = Some(("Scanner Error".into(), err.to_string()));
+                        scanner_settings::Action::ListDevicesFailed(errors) => {
+                            self.state.error = Some(("Scanner Error".into(), errors.join("\n")));
                         }
                         _ => (),
                     }
@@ -192,20 +330,82 @@ impl App for Application {
                     self.scanner_settings
                         .update(&mut self.state.scanner_settings, scanner_settings);
                 }
-                Action::GotBarcodeData(data) => {
+                Action::GotBarcodeData(source, data) => {
                     self.state.decoder_loading -= 1;
 
+                    // Any data matches the generic decoder, so it doesn't
+                    // count as a good read.
+                    let matched = data.as_ref().is_some_and(|entry| {
+                        entry.decoder_name != barcode_decoders::GENERIC_DECODER_NAME
+                    });
+                    self.acknowledge(&source, matched);
+
                     if let Some(data) = data {
                         self.state.decoded_history.push_front(data);
                         self.state.decoded_history.truncate(20);
                     }
                 }
-                Action::DecoderToggled | Action::Decoder(_) => (),
+                Action::ImagesSelected(paths) => {
+                    for path in paths {
+                        self.open_image(file_name(&path), Some(path), None);
+                    }
+                }
+                Action::ScansFound(source, result) => {
+                    self.state.decoder_loading -= 1;
+
+                    match result {
+                        Ok(scans) if scans.is_empty() => {
+                            self.state.error = Some((
+                                "No Barcodes Found".into(),
+                                format!("No barcodes were found in {source}."),
+                            ));
+                        }
+                        Ok(scans) => {
+                            for scan in scans {
+                                self.worker.send(Action::ScannerSettings(
+                                    scanner_settings::Action::ScannedBarcode(
+                                        source.clone(),
+                                        Ok(scan),
+                                    ),
+                                ));
+                            }
+                        }
+                        Err(err) => {
+                            self.state.error = Some((
+                                "Scan Error".into(),
+                                format!("Could not scan {source}: {err}"),
+                            ));
+                        }
+                    }
+                }
+                Action::DecoderToggled => (),
+                Action::Decoder(action) => {
+                    if let Some((title, err)) = action.error() {
+                        self.state.error = Some((title.into(), err.to_string()));
+                    }
+                }
             }
 
             tracing::debug!(state = ?self.state, "built new state");
         }
 
+        for expired in self.state.reassembler.expire() {
+            self.state.error = Some((
+                "Incomplete Barcode".into(),
+                format!("Gave up waiting for the rest of a multi-part barcode, {expired}."),
+            ));
+        }
+
+        if !self.state.reassembler.is_empty() {
+            ctx.request_repaint_after(Duration::from_secs(1));
+        }
+
+        for file in ctx.input(|i| i.raw.dropped_files.clone()) {
+            let name = file.path.as_deref().map(file_name).unwrap_or(file.name);
+
+            self.open_image(name, file.path, file.bytes.map(|bytes| bytes.to_vec()));
+        }
+
         Window::new("Scanner Settings")
             .open(&mut self.state.scanner_settings_open)
             .resizable(false)
@@ -225,6 +425,38 @@ impl App for Application {
             const SAVE_SHORTCUT: KeyboardShortcut = KeyboardShortcut::new(MAIN_KEY, Key::S);
             const CLEAR_SHORTCUT: KeyboardShortcut = KeyboardShortcut::new(MAIN_KEY, Key::R);
             const PASTE_SHORTCUT: KeyboardShortcut = KeyboardShortcut::new(MAIN_KEY, Key::V);
+            // egui only pastes with Shift+Insert on Windows.
+            const SHIFT_INSERT_SHORTCUT: KeyboardShortcut =
+                KeyboardShortcut::new(Modifiers::SHIFT, Key::Insert);
+
+            let paste_keys_down = &mut self.state.paste_keys_down;
+            let (paste, paste_command) = ui.input(|i| {
+                let paste = i.events.iter().find_map(|ev| match ev {
+                    Event::Paste(value) => Some(value.clone()),
+                    _ => None,
+                });
+
+                // Pasting only creates an event when the clipboard has text,
+                // so a paste key being released without having been seen
+                // pressed is used to notice when it didn't.
+                let mut paste_command = false;
+                for ev in &i.events {
+                    if let Event::Key {
+                        key: key @ (Key::V | Key::Insert | Key::Paste),
+                        pressed,
+                        ..
+                    } = ev
+                    {
+                        if *pressed {
+                            paste_keys_down.insert(*key);
+                        } else if !paste_keys_down.remove(key) {
+                            paste_command = true;
+                        }
+                    }
+                }
+
+                (paste, paste_command)
+            });
 
             if ui.input_mut(|i| i.consume_shortcut(&SAVE_SHORTCUT)) {
                 self.save_config();
@@ -234,23 +466,50 @@ impl App for Application {
                 self.state.clear_history();
             }
 
-            if let Some(paste) = ui.input(|i| {
-                i.events.iter().find_map(|ev| match ev {
-                    Event::Paste(value) => Some(value.clone()),
-                    _ => None,
-                })
-            }) {
+            if ui.input_mut(|i| i.consume_shortcut(&SHIFT_INSERT_SHORTCUT)) {
+                self.scan_clipboard(true);
+            }
+
+            if let Some(paste) = paste {
+                self.state.pasted_text = true;
+
                 self.worker.send(Action::ScannerSettings(
-                    scanner_settings::Action::ScannedBarcode(Ok(paste)),
+                    scanner_settings::Action::ScannedBarcode(
+                        ScanSource::Clipboard,
+                        Ok(Scan::from_text(paste)),
+                    ),
                 ));
             }
 
+            if paste_command && !std::mem::take(&mut self.state.pasted_text) {
+                self.scan_clipboard(false);
+            }
+
             menu::bar(ui, |ui| {
                 eframe::egui::global_dark_light_mode_switch(ui);
 
                 ui.separator();
 
                 ui.menu_button("File", |ui| {
+                    if ui.button("Open Image…").clicked() {
+                        ui.close_menu();
+
+                        self.worker.perform(async move {
+                            let paths = rfd::AsyncFileDialog::new()
+                                .add_filter("Images", IMAGE_EXTENSIONS)
+                                .pick_files()
+                                .await
+                                .unwrap_or_default()
+                                .into_iter()
+                                .map(|file| file.path().to_path_buf())
+                                .collect();
+
+                            Action::ImagesSelected(paths)
+                        });
+                    }
+
+                    ui.separator();
+
                     if ui
                         .add(
                             Button::new("Save Settings")
@@ -273,19 +532,36 @@ impl App for Application {
                         self.state.clear_history();
                     }
 
-                    ui.add_enabled_ui(false, |ui| {
-                        ui.add(
+                    if ui
+                        .add(
                             Button::new("Scan from Clipboard")
                                 .shortcut_text(ui.ctx().format_shortcut(&PASTE_SHORTCUT)),
                         )
-                        .on_disabled_hover_text("Use the paste shortcut");
-                    });
+                        .clicked()
+                    {
+                        ui.close_menu();
+                        self.scan_clipboard(true);
+                    }
                 });
 
                 ui.menu_button("Settings", |ui| {
                     if ui.button("Scanner Setup").clicked() {
                         self.state.scanner_settings_open = true;
                     }
+
+                    ui.menu_button("Duplicate Scans", |ui| {
+                        self.state.duplicate_policy.settings(ui);
+                    });
+                });
+
+                ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
+                    if self
+                        .scanner_settings
+                        .status(&self.state.scanner_settings, ui)
+                        .clicked()
+                    {
+                        self.state.scanner_settings_open = true;
+                    }
                 });
             });
         });
@@ -336,8 +612,34 @@ impl App for Application {
                     });
                 }
 
-                for (decoder_name, data) in self.state.decoded_history.iter() {
-                    ui.label(*decoder_name);
+                for progress in self.state.reassembler.progress() {
+                    ui.horizontal(|ui| {
+                        ui.label(format!("Multi-part barcode, {progress}"));
+                        ui.spinner();
+                    });
+                }
+
+                for HistoryEntry {
+                    source,
+                    decoder_name,
+                    symbology,
+                    data,
+                    duplicate,
+                } in self.state.decoded_history.iter()
+                {
+                    ui.horizontal(|ui| {
+                        ui.label(*decoder_name);
+
+                        if let Some(symbology) = symbology {
+                            ui.weak(symbology.to_string());
+                        }
+
+                        ui.weak(source.to_string());
+
+                        if *duplicate {
+                            ui.colored_label(ui.visuals().warn_fg_color, "Duplicate");
+                        }
+                    });
 
                     CollapsingHeader::new(data.summary())
                         .id_source(data.id())
@@ -365,6 +667,13 @@ impl App for Application {
     }
 }
 
+/// Get a displayable name for a file.
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.display().to_string())
+}
+
 pub(crate) fn show_ui() -> eyre::Result<()> {
     let (tx, rx) = unbounded_channel();
 
@@ -407,9 +716,9 @@ pub(crate) fn show_ui() -> eyre::Result<()> {
             .unwrap_or_else(|_| ConfigLoader::empty(settings_path))
     });
 
-    // Load devices immediately.
+    // Start watching for devices, which loads them immediately.
     worker.send(Action::ScannerSettings(
-        scanner_settings::Action::ReloadDevices,
+        scanner_settings::Action::WatchDevices,
     ));
 
     run_native(
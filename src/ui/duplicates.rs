@@ -0,0 +1,153 @@
+use std::time::{Duration, Instant};
+
+use eframe::egui::{DragValue, Ui};
+use enum_iterator::Sequence;
+use serde::{Deserialize, Serialize};
+
+use crate::barcode_scanner::{Scan, ScanSource};
+
+/// What to do with a scan that was recently seen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Sequence, Serialize, Deserialize)]
+pub(crate) enum DuplicateAction {
+    /// Drop the scan.
+    #[default]
+    Ignore,
+    /// Show the scan, but mark it as a duplicate.
+    Warn,
+    /// Show the scan as if it was new.
+    Accept,
+}
+
+impl std::fmt::Display for DuplicateAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Ignore => write!(f, "Ignore"),
+            Self::Warn => write!(f, "Warn but Show"),
+            Self::Accept => write!(f, "Always Accept"),
+        }
+    }
+}
+
+/// Which scans are compared when looking for duplicates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Sequence, Serialize, Deserialize)]
+pub(crate) enum DuplicateScope {
+    /// Scans from any source.
+    #[default]
+    Global,
+    /// Only scans from the same source.
+    PerSource,
+}
+
+impl std::fmt::Display for DuplicateScope {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Global => write!(f, "All Sources"),
+            Self::PerSource => write!(f, "Per Source"),
+        }
+    }
+}
+
+/// How to handle the same barcode being scanned again.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct DuplicatePolicy {
+    pub(crate) action: DuplicateAction,
+    pub(crate) scope: DuplicateScope,
+    /// How long after a scan the same data is considered a duplicate.
+    pub(crate) window_secs: f32,
+}
+
+impl Default for DuplicatePolicy {
+    fn default() -> Self {
+        Self {
+            action: Default::default(),
+            scope: Default::default(),
+            window_secs: 5.0,
+        }
+    }
+}
+
+impl DuplicatePolicy {
+    fn window(&self) -> Duration {
+        Duration::from_secs_f32(self.window_secs.max(0.0))
+    }
+
+    /// Render the policy's settings.
+    pub(crate) fn settings(&mut self, ui: &mut Ui) {
+        for action in enum_iterator::all::<DuplicateAction>() {
+            ui.radio_value(&mut self.action, action, action.to_string());
+        }
+
+        ui.separator();
+
+        ui.add_enabled_ui(self.action != DuplicateAction::Accept, |ui| {
+            for scope in enum_iterator::all::<DuplicateScope>() {
+                ui.radio_value(&mut self.scope, scope, scope.to_string());
+            }
+
+            ui.horizontal(|ui| {
+                ui.label("Window");
+                ui.add(
+                    DragValue::new(&mut self.window_secs)
+                        .clamp_range(0.0..=3600.0)
+                        .speed(0.5)
+                        .suffix(" s"),
+                );
+            });
+        });
+    }
+}
+
+#[derive(Debug)]
+struct RecentScan {
+    source: ScanSource,
+    scan: Scan,
+    seen_at: Instant,
+}
+
+/// Remembers recent scans to find duplicates.
+#[derive(Debug, Default)]
+pub(crate) struct DuplicateFilter {
+    recent: Vec<RecentScan>,
+}
+
+impl DuplicateFilter {
+    /// Record a scan, returning if it duplicates a recent scan under the
+    /// policy.
+    pub(crate) fn check(
+        &mut self,
+        policy: &DuplicatePolicy,
+        source: &ScanSource,
+        scan: &Scan,
+    ) -> bool {
+        let now = Instant::now();
+        let window = policy.window();
+
+        self.recent
+            .retain(|recent| now.duration_since(recent.seen_at) < window);
+
+        let existing = self.recent.iter_mut().find(|recent| {
+            recent.scan == *scan
+                && (policy.scope == DuplicateScope::Global || recent.source == *source)
+        });
+
+        match existing {
+            Some(recent) => {
+                recent.seen_at = now;
+                true
+            }
+            None => {
+                self.recent.push(RecentScan {
+                    source: source.clone(),
+                    scan: scan.clone(),
+                    seen_at: now,
+                });
+                false
+            }
+        }
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.recent.clear();
+    }
+}
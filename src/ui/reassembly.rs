@@ -0,0 +1,299 @@
+use std::{
+    collections::BTreeMap,
+    time::{Duration, Instant},
+};
+
+use crate::barcode_scanner::{Scan, ScanSource};
+
+/// How long to wait for the next part of a set before discarding it.
+const PART_TIMEOUT: Duration = Duration::from_secs(60);
+
+#[derive(Debug)]
+struct PartialSet {
+    parts: BTreeMap<usize, Scan>,
+    total: Option<usize>,
+    updated_at: Instant,
+}
+
+impl PartialSet {
+    fn is_complete(&self) -> bool {
+        self.total
+            .is_some_and(|total| self.parts.keys().copied().eq(0..total))
+    }
+
+    /// Combine the data of every part, in order.
+    fn combine(self) -> Scan {
+        let symbology = self.parts.values().next().and_then(|part| part.symbology());
+
+        let data = self
+            .parts
+            .into_values()
+            .flat_map(|part| part.data().to_vec())
+            .collect();
+
+        Scan::from_bytes(data).with_symbology(symbology)
+    }
+}
+
+/// The progress of a set of parts that hasn't been completed.
+#[derive(Debug)]
+pub(crate) struct SetProgress {
+    pub(crate) received: usize,
+    pub(crate) total: Option<usize>,
+}
+
+impl std::fmt::Display for SetProgress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.total {
+            Some(total) => write!(f, "{} of {} scanned", self.received, total),
+            None => write!(f, "{} scanned", self.received),
+        }
+    }
+}
+
+/// Buffers scans that are part of a payload split across multiple symbols
+/// until every part has been scanned.
+#[derive(Debug, Default)]
+pub(crate) struct Reassembler {
+    sets: BTreeMap<String, PartialSet>,
+}
+
+impl Reassembler {
+    /// Add a scan, returning a scan ready to be decoded.
+    ///
+    /// Scans that aren't part of a set are returned immediately, and parts are
+    /// only returned once combined with the rest of their set.
+    pub(crate) fn push(&mut self, source: &ScanSource, scan: Scan) -> Option<Scan> {
+        let Some(part) = scan.part().cloned() else {
+            return Some(scan);
+        };
+
+        tracing::debug!(?part, "got part of set");
+
+        let group = if part.per_source {
+            format!("{source}/{}", part.group)
+        } else {
+            part.group.clone()
+        };
+
+        let set = self
+            .sets
+            .entry(group.clone())
+            .or_insert_with(|| PartialSet {
+                parts: BTreeMap::new(),
+                total: None,
+                updated_at: Instant::now(),
+            });
+
+        // A different part at the same position means a new set was started
+        // before the previous one finished.
+        if set
+            .parts
+            .get(&part.index)
+            .is_some_and(|existing| existing.data() != scan.data())
+        {
+            tracing::debug!(group, "part was repeated, starting new set");
+            set.parts.clear();
+            set.total = None;
+        }
+
+        set.parts.insert(part.index, scan);
+        set.total = set.total.or(part.total);
+        set.updated_at = Instant::now();
+
+        if !set.is_complete() {
+            return None;
+        }
+
+        self.sets.remove(&group).map(PartialSet::combine)
+    }
+
+    /// Remove sets that haven't been added to recently, returning their
+    /// progress.
+    pub(crate) fn expire(&mut self) -> Vec<SetProgress> {
+        let mut expired = Vec::new();
+
+        self.sets.retain(|_group, set| {
+            if set.updated_at.elapsed() < PART_TIMEOUT {
+                return true;
+            }
+
+            expired.push(SetProgress {
+                received: set.parts.len(),
+                total: set.total,
+            });
+
+            false
+        });
+
+        expired
+    }
+
+    /// The progress of every incomplete set.
+    pub(crate) fn progress(&self) -> impl Iterator<Item = SetProgress> + '_ {
+        self.sets.values().map(|set| SetProgress {
+            received: set.parts.len(),
+            total: set.total,
+        })
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.sets.is_empty()
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.sets.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::barcode_scanner::{ScanPart, Symbology};
+
+    use super::*;
+
+    fn part(group: &str, index: usize, total: Option<usize>, data: &str) -> Scan {
+        Scan::from_text(data.to_string())
+            .with_symbology(Some(Symbology::QrCode))
+            .with_part(Some(ScanPart {
+                group: group.to_string(),
+                index,
+                total,
+                per_source: false,
+            }))
+    }
+
+    fn counter_part(index: usize, total: usize, data: &str) -> Scan {
+        Scan::from_text(data.to_string()).with_part(Some(ScanPart {
+            group: format!("parts-{total}"),
+            index,
+            total: Some(total),
+            per_source: true,
+        }))
+    }
+
+    #[test]
+    fn passes_through_scans_without_parts() {
+        let mut reassembler = Reassembler::default();
+        let scan = Scan::from_text("[1/2] Lot A".to_string());
+
+        assert_eq!(
+            reassembler.push(&ScanSource::Clipboard, scan.clone()),
+            Some(scan)
+        );
+        assert!(reassembler.is_empty());
+    }
+
+    #[test]
+    fn completes_out_of_order() {
+        let mut reassembler = Reassembler::default();
+        let source = ScanSource::Clipboard;
+
+        assert_eq!(reassembler.push(&source, part("a", 2, Some(3), "c")), None);
+        assert_eq!(reassembler.push(&source, part("a", 0, Some(3), "a")), None);
+
+        let progress: Vec<_> = reassembler.progress().map(|p| p.to_string()).collect();
+        assert_eq!(progress, ["2 of 3 scanned"]);
+
+        let scan = reassembler
+            .push(&source, part("a", 1, Some(3), "b"))
+            .unwrap();
+        assert_eq!(scan.text(), Some("abc"));
+        assert_eq!(scan.symbology(), Some(Symbology::QrCode));
+        assert_eq!(scan.part(), None);
+        assert!(reassembler.is_empty());
+    }
+
+    #[test]
+    fn waits_for_total() {
+        let mut reassembler = Reassembler::default();
+        let source = ScanSource::Clipboard;
+
+        assert_eq!(reassembler.push(&source, part("pdf", 0, None, "a")), None);
+        let scan = reassembler
+            .push(&source, part("pdf", 1, Some(2), "b"))
+            .unwrap();
+        assert_eq!(scan.text(), Some("ab"));
+    }
+
+    #[test]
+    fn keeps_sets_separate() {
+        let mut reassembler = Reassembler::default();
+        let source = ScanSource::Clipboard;
+
+        assert_eq!(reassembler.push(&source, part("a", 0, Some(2), "a")), None);
+        assert_eq!(reassembler.push(&source, part("b", 1, Some(2), "B")), None);
+
+        let scan = reassembler
+            .push(&source, part("b", 0, Some(2), "A"))
+            .unwrap();
+        assert_eq!(scan.text(), Some("AB"));
+        assert_eq!(reassembler.progress().count(), 1);
+    }
+
+    #[test]
+    fn scopes_counter_groups_to_source() {
+        let mut reassembler = Reassembler::default();
+        let first = ScanSource::Image("first.png".to_string());
+        let second = ScanSource::Image("second.png".to_string());
+
+        assert_eq!(reassembler.push(&first, counter_part(0, 2, "a")), None);
+        assert_eq!(reassembler.push(&second, counter_part(1, 2, "X")), None);
+        assert_eq!(reassembler.progress().count(), 2);
+
+        let scan = reassembler.push(&first, counter_part(1, 2, "b")).unwrap();
+        assert_eq!(scan.text(), Some("ab"));
+    }
+
+    #[test]
+    fn restarts_set_when_part_repeats() {
+        let mut reassembler = Reassembler::default();
+        let source = ScanSource::Clipboard;
+
+        assert_eq!(reassembler.push(&source, counter_part(0, 3, "old")), None);
+        assert_eq!(reassembler.push(&source, counter_part(1, 3, "old")), None);
+
+        // Scanning the same part again doesn't lose progress.
+        assert_eq!(reassembler.push(&source, counter_part(1, 3, "old")), None);
+        assert_eq!(
+            reassembler
+                .progress()
+                .map(|p| p.received)
+                .collect::<Vec<_>>(),
+            [2]
+        );
+
+        assert_eq!(reassembler.push(&source, counter_part(0, 3, "a")), None);
+        assert_eq!(
+            reassembler
+                .progress()
+                .map(|p| p.received)
+                .collect::<Vec<_>>(),
+            [1]
+        );
+
+        assert_eq!(reassembler.push(&source, counter_part(1, 3, "b")), None);
+        let scan = reassembler.push(&source, counter_part(2, 3, "c")).unwrap();
+        assert_eq!(scan.text(), Some("abc"));
+    }
+
+    #[test]
+    fn expires_stale_sets() {
+        let mut reassembler = Reassembler::default();
+        let source = ScanSource::Clipboard;
+
+        reassembler.push(&source, part("stale", 0, Some(2), "a"));
+        reassembler.push(&source, part("fresh", 0, Some(3), "a"));
+        assert!(reassembler.expire().is_empty());
+
+        let set = reassembler.sets.get_mut("stale").unwrap();
+        set.updated_at = Instant::now()
+            .checked_sub(PART_TIMEOUT + Duration::from_secs(1))
+            .unwrap();
+
+        let expired = reassembler.expire();
+        assert_eq!(expired.len(), 1);
+        assert_eq!(expired[0].to_string(), "1 of 2 scanned");
+        assert_eq!(reassembler.progress().count(), 1);
+    }
+}
@@ -1,49 +1,178 @@
-use eframe::egui::{ComboBox, Grid, Ui};
+use std::{collections::BTreeMap, time::Duration};
+
+use eframe::egui::{
+    Color32, ComboBox, DragValue, Grid, Label, Response, RichText, Sense, TextEdit, Ui,
+};
 use futures::StreamExt;
 use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc::UnboundedSender;
 use tokio_util::sync::CancellationToken;
 
-use crate::barcode_scanner::{Device, DeviceType, HidType};
+use crate::barcode_scanner::{
+    Device, DeviceType, FramingConfig, HidType, KeyboardLayout, NetworkMode, Scan, ScanSource,
+    ScannerCommand, ScannerOptions, SerialConfig, Terminator,
+};
 
 use super::StateWorker;
 
+/// How often to check for devices being added or removed.
+const DEVICE_POLL_INTERVAL: Duration = Duration::from_secs(2);
+const MAX_RECONNECT_DELAY: Duration = Duration::from_secs(30);
+
 #[derive(Debug)]
 pub(crate) enum Action {
     ReloadDevices,
-    LoadedDevices(Vec<Device>),
+    WatchDevices,
+    /// Devices that were found, and errors from sources that failed.
+    LoadedDevices(Vec<Device>, Vec<String>),
+    /// Some sources could not be enumerated.
+    ListDevicesFailed(Vec<String>),
     SelectedDevice(Option<Device>),
-    ConnectDevice,
-    DisconnectDevice,
-    ScannedBarcode(eyre::Result<String>),
+    AddManualDevice(Device),
+    RemoveManualDevice(Device),
+    ConnectDevice(Device),
+    RetryConnect(Device),
+    DisconnectDevice(Device),
+    ScannedBarcode(ScanSource, eyre::Result<Scan>),
+    SendCommand(Device, ScannerCommand),
+}
+
+/// The options to use for a device that hasn't been configured yet.
+fn default_options(device: &Device) -> ScannerOptions {
+    crate::barcode_scanner::source_for(&device.device_type)
+        .map(|source| source.default_options(device))
+        .unwrap_or_default()
+}
+
+#[derive(Debug)]
+enum Connection {
+    Connected {
+        token: CancellationToken,
+        commands: UnboundedSender<ScannerCommand>,
+        /// The number of reconnection attempts since the last successful
+        /// scan.
+        attempt: u32,
+    },
+    /// The device stopped working and should be reconnected once available.
+    Reconnecting { attempt: u32, error: Option<String> },
+}
+
+impl Connection {
+    fn attempt(&self) -> u32 {
+        match self {
+            Self::Connected { attempt, .. } | Self::Reconnecting { attempt, .. } => *attempt,
+        }
+    }
+
+    fn status(&self) -> String {
+        match self {
+            Self::Connected { .. } => "Connected".to_string(),
+            Self::Reconnecting {
+                error: Some(error), ..
+            } => format!("Reconnecting ({error})"),
+            Self::Reconnecting { error: None, .. } => "Waiting for device".to_string(),
+        }
+    }
 }
 
 #[derive(Debug, Default)]
 pub(crate) struct State {
     devices: Vec<Device>,
-    baud_rate: u32,
-    hid_type: HidType,
+    /// Devices that can't be discovered, so they were added manually.
+    manual_devices: Vec<Device>,
+    new_network_mode: NetworkMode,
+    new_network_address: String,
+    new_replay_path: String,
+    /// The device being shown in the settings.
     selected_device: Option<Device>,
-    connected_scanner_token: Option<CancellationToken>,
+    device_settings: BTreeMap<Device, ScannerOptions>,
+    connections: BTreeMap<Device, Connection>,
+    /// Errors from the last time devices were listed.
+    device_errors: Vec<String>,
     pub saved_config: Option<SavedConfig>,
 }
 
 impl State {
     pub(crate) fn saved(&self) -> SavedConfig {
         SavedConfig {
-            baud_rate: Some(self.baud_rate),
-            hid_type: Some(self.hid_type),
-            selected_device: self.selected_device.clone(),
+            devices: self
+                .device_settings
+                .iter()
+                .map(|(device, settings)| SavedDevice {
+                    device: device.clone(),
+                    settings: settings.clone(),
+                    connected: self.connections.contains_key(device),
+                })
+                .collect(),
+            ..Default::default()
         }
     }
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SavedDevice {
+    device: Device,
+    settings: ScannerOptions,
+    connected: bool,
+}
+
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct SavedConfig {
+    #[serde(default)]
+    devices: Vec<SavedDevice>,
+
+    // The remaining fields are only used for reading configs saved before
+    // multiple devices could be connected.
+    #[serde(default, skip_serializing)]
     baud_rate: Option<u32>,
+    #[serde(default, skip_serializing)]
+    serial: Option<SerialConfig>,
+    #[serde(default, skip_serializing)]
     hid_type: Option<HidType>,
+    #[serde(default, skip_serializing)]
+    keyboard_layout: Option<KeyboardLayout>,
+    #[serde(default, skip_serializing)]
+    framing: Option<FramingConfig>,
+    #[serde(default, skip_serializing)]
     selected_device: Option<Device>,
 }
 
+impl SavedConfig {
+    fn manual_devices(&self) -> impl Iterator<Item = Device> + '_ {
+        self.devices
+            .iter()
+            .filter(|saved_device| saved_device.device.device_type.is_manual())
+            .map(|saved_device| saved_device.device.clone())
+    }
+
+    fn into_devices(self) -> Vec<SavedDevice> {
+        let Some(device) = self.selected_device.filter(|_| self.devices.is_empty()) else {
+            return self.devices;
+        };
+
+        let serial = match (self.serial, self.baud_rate) {
+            (Some(serial), _) => serial,
+            (None, Some(baud_rate)) => SerialConfig {
+                baud_rate,
+                ..Default::default()
+            },
+            (None, None) => Default::default(),
+        };
+
+        vec![SavedDevice {
+            device,
+            settings: ScannerOptions {
+                serial,
+                hid_type: self.hid_type.unwrap_or_default(),
+                keyboard_layout: self.keyboard_layout.unwrap_or_default(),
+                framing: self.framing.unwrap_or_default(),
+                ..Default::default()
+            },
+            connected: true,
+        }]
+    }
+}
+
 impl State {
     fn selected_device_name(&self) -> &str {
         match self.selected_device.as_ref() {
@@ -52,43 +181,38 @@ impl State {
         }
     }
 
-    fn is_hid_device(&self) -> bool {
-        matches!(
-            self.selected_device,
-            Some(Device {
-                device_type: DeviceType::Hid { .. },
-                ..
-            })
-        )
+    /// If the device is connected or waiting to be reconnected.
+    fn is_connected(&self, device: &Device) -> bool {
+        self.connections.contains_key(device)
     }
 
-    fn is_serial_device(&self) -> bool {
-        matches!(
-            self.selected_device,
-            Some(Device {
-                device_type: DeviceType::Serial { .. },
-                ..
-            })
-        )
+    fn disconnect(&mut self, device: &Device) {
+        if let Some(Connection::Connected { token, .. }) = self.connections.remove(device) {
+            token.cancel();
+        }
     }
 
-    fn can_connect(&self) -> bool {
-        match self.selected_device {
-            Some(Device {
-                device_type: DeviceType::Serial { .. },
-                ..
-            }) if self.baud_rate > 0 => true,
-            Some(Device {
-                device_type: DeviceType::Hid { .. },
-                ..
-            }) => true,
-            _ => false,
+    /// Mark a connected device as needing to be reconnected, returning the
+    /// attempt number if it was previously connected.
+    fn connection_lost(&mut self, device: &Device, error: Option<String>) -> Option<u32> {
+        let connection = self.connections.get_mut(device)?;
+
+        match connection {
+            Connection::Connected { token, attempt, .. } => {
+                token.cancel();
+
+                let attempt = *attempt + 1;
+                *connection = Connection::Reconnecting { attempt, error };
+
+                Some(attempt)
+            }
+            Connection::Reconnecting { .. } => None,
         }
     }
+}
 
-    fn is_connected(&self) -> bool {
-        self.connected_scanner_token.is_some()
-    }
+fn reconnect_delay(attempt: u32) -> Duration {
+    (Duration::from_millis(500) * 2u32.pow(attempt.min(6))).min(MAX_RECONNECT_DELAY)
 }
 
 pub(crate) struct BarcodeSettings {
@@ -101,22 +225,96 @@ impl BarcodeSettings {
             .apply(&mut state, action, |state, action| match action {
                 Action::ReloadDevices => {
                     self.worker.perform(async move {
-                        let devices = crate::barcode_scanner::list_devices().await.unwrap();
-                        Action::LoadedDevices(devices)
+                        let (devices, errors) = crate::barcode_scanner::list_devices().await;
+                        Action::LoadedDevices(devices, errors)
+                    });
+                }
+                Action::WatchDevices => {
+                    self.worker.stream(async move {
+                        let interval = tokio::time::interval(DEVICE_POLL_INTERVAL);
+
+                        tokio_stream::wrappers::IntervalStream::new(interval)
+                            .then(|_| crate::barcode_scanner::list_devices())
+                            .map(|(devices, errors)| Action::LoadedDevices(devices, errors))
                     });
                 }
-                Action::LoadedDevices(devices) => {
+                Action::LoadedDevices(mut devices, errors) => {
+                    // Devices are polled, so only report errors when they
+                    // change.
+                    if errors != state.device_errors {
+                        if !errors.is_empty() {
+                            self.worker.send(Action::ListDevicesFailed(errors.clone()));
+                        }
+
+                        state.device_errors = errors;
+                    }
+
+                    if let Some(saved_config) = &state.saved_config {
+                        for device in saved_config.manual_devices() {
+                            if !state.manual_devices.contains(&device) {
+                                state.manual_devices.push(device);
+                            }
+                        }
+                    }
+
+                    devices.extend(state.manual_devices.iter().cloned());
+
+                    let (appeared, disappeared): (Vec<_>, Vec<_>) = devices
+                        .iter()
+                        .filter(|device| !state.devices.contains(device))
+                        .map(|device| (device.clone(), true))
+                        .chain(
+                            state
+                                .devices
+                                .iter()
+                                .filter(|device| !devices.contains(device))
+                                .map(|device| (device.clone(), false)),
+                        )
+                        .partition(|(_, appeared)| *appeared);
+
                     state.devices = devices;
 
+                    for (device, _) in disappeared {
+                        tracing::info!(name = device.name, "device was removed");
+                        state.connection_lost(&device, Some("device was removed".to_string()));
+                    }
+
+                    for (device, _) in appeared {
+                        if matches!(
+                            state.connections.get(&device),
+                            Some(Connection::Reconnecting { .. })
+                        ) {
+                            tracing::info!(name = device.name, "device was added, reconnecting");
+                            self.worker.send(Action::RetryConnect(device));
+                        }
+                    }
+
                     if let Some(saved_config) = state.saved_config.take() {
-                        state.baud_rate = saved_config.baud_rate.unwrap_or_default();
-                        state.hid_type = saved_config.hid_type.unwrap_or_default();
+                        for saved_device in saved_config.into_devices() {
+                            let available = state.devices.contains(&saved_device.device);
 
-                        if let Some(saved_device) = saved_config.selected_device {
-                            if state.devices.iter().any(|device| device == &saved_device) {
-                                state.selected_device = Some(saved_device);
-                                self.worker.send(Action::ConnectDevice);
+                            if saved_device.connected && !state.is_connected(&saved_device.device) {
+                                if available {
+                                    self.worker
+                                        .send(Action::ConnectDevice(saved_device.device.clone()));
+                                } else {
+                                    state.connections.insert(
+                                        saved_device.device.clone(),
+                                        Connection::Reconnecting {
+                                            attempt: 0,
+                                            error: None,
+                                        },
+                                    );
+                                }
                             }
+
+                            if available && state.selected_device.is_none() {
+                                state.selected_device = Some(saved_device.device.clone());
+                            }
+
+                            state
+                                .device_settings
+                                .insert(saved_device.device, saved_device.settings);
                         }
                     } else if let Some(selected_device) = &state.selected_device {
                         if !state.devices.iter().any(|device| device == selected_device) {
@@ -125,74 +323,232 @@ impl BarcodeSettings {
                     }
                 }
                 Action::SelectedDevice(Some(device)) => {
-                    if let DeviceType::Hid { usage_id, .. } = device.device_type {
-                        state.hid_type = if usage_id == 6 {
-                            HidType::Keyboard
-                        } else {
-                            HidType::Pos
-                        };
-                    }
+                    state
+                        .device_settings
+                        .entry(device.clone())
+                        .or_insert_with(|| default_options(&device));
                 }
                 Action::SelectedDevice(_) => (),
-                Action::ConnectDevice => {
-                    let Some(device) = state.selected_device.clone() else {
-                        return;
-                    };
-
-                    let baud = Some(state.baud_rate);
-                    let hid_type = Some(state.hid_type);
+                Action::AddManualDevice(device) => {
+                    if !state.manual_devices.contains(&device) {
+                        state.manual_devices.push(device.clone());
+                        state.devices.push(device.clone());
+                    }
 
-                    let token = CancellationToken::new();
-                    state.connected_scanner_token = Some(token.clone());
+                    state
+                        .device_settings
+                        .entry(device.clone())
+                        .or_insert_with(|| default_options(&device));
+                    state.selected_device = Some(device);
+                }
+                Action::RemoveManualDevice(device) => {
+                    state.disconnect(&device);
 
-                    self.worker.stream(async move {
-                        let tx = crate::barcode_scanner::start_scanner(
-                            token,
-                            device.device_type,
-                            baud,
-                            hid_type,
-                        )
-                        .await
-                        .expect("could not start scanner");
+                    state.manual_devices.retain(|d| d != &device);
+                    state.devices.retain(|d| d != &device);
+                    state.device_settings.remove(&device);
 
-                        tokio_stream::wrappers::ReceiverStream::from(tx).map(Action::ScannedBarcode)
-                    });
+                    if state.selected_device.as_ref() == Some(&device) {
+                        state.selected_device = None;
+                    }
                 }
-                Action::DisconnectDevice => {
-                    if let Some(token) = state.connected_scanner_token.take() {
-                        token.cancel();
+                Action::ConnectDevice(device) => {
+                    if state.is_connected(&device) {
+                        return;
                     }
+
+                    self.connect(state, device);
                 }
-                Action::ScannedBarcode(Err(_)) => {
-                    if let Some(token) = state.connected_scanner_token.take() {
-                        token.cancel();
+                Action::RetryConnect(device) => {
+                    if matches!(
+                        state.connections.get(&device),
+                        Some(Connection::Reconnecting { .. })
+                    ) && state.devices.contains(&device)
+                    {
+                        self.connect(state, device);
                     }
+                }
+                Action::DisconnectDevice(device) => {
+                    state.disconnect(&device);
+                }
+                Action::ScannedBarcode(ScanSource::Device(device), Err(err)) => {
+                    if let Some(attempt) = state.connection_lost(&device, Some(err.to_string())) {
+                        let delay = reconnect_delay(attempt);
+                        tracing::info!(name = device.name, attempt, ?delay, "scheduling reconnect");
 
-                    self.worker.send(Action::ReloadDevices);
+                        self.worker.perform(async move {
+                            tokio::time::sleep(delay).await;
+                            Action::RetryConnect(device)
+                        });
+                    }
+                }
+                Action::ScannedBarcode(ScanSource::Device(device), Ok(_)) => {
+                    if let Some(Connection::Connected { attempt, .. }) =
+                        state.connections.get_mut(&device)
+                    {
+                        *attempt = 0;
+                    }
+                }
+                Action::ScannedBarcode(..) | Action::ListDevicesFailed(_) => (),
+                Action::SendCommand(device, command) => {
+                    if let Some(Connection::Connected { commands, .. }) =
+                        state.connections.get(&device)
+                    {
+                        if let Err(err) = commands.send(command) {
+                            tracing::warn!("could not send scanner command: {err}");
+                        }
+                    }
                 }
-                Action::ScannedBarcode(_) => (),
             });
     }
 
+    fn connect(&self, state: &mut State, device: Device) {
+        let attempt = state
+            .connections
+            .get(&device)
+            .map(Connection::attempt)
+            .unwrap_or_default();
+
+        let options = state
+            .device_settings
+            .entry(device.clone())
+            .or_insert_with(|| default_options(&device))
+            .clone();
+
+        let token = CancellationToken::new();
+        let (command_tx, command_rx) = tokio::sync::mpsc::unbounded_channel();
+
+        state.connections.insert(
+            device.clone(),
+            Connection::Connected {
+                token: token.clone(),
+                commands: command_tx,
+                attempt,
+            },
+        );
+
+        self.worker.stream(async move {
+            let source = ScanSource::Device(device.clone());
+
+            // Reconnects don't check if the device can still be connected, so
+            // failing to start is handled like losing the connection.
+            let scans = match crate::barcode_scanner::start_scanner(
+                token,
+                device.device_type,
+                options,
+                command_rx,
+            )
+            .await
+            {
+                Ok(rx) => tokio_stream::wrappers::ReceiverStream::from(rx).boxed(),
+                Err(err) => futures::stream::once(async move { Err(err) }).boxed(),
+            };
+
+            scans.map(move |scan| Action::ScannedBarcode(source.clone(), scan))
+        });
+    }
+
+    /// Show a summary of the connection status of all devices.
+    pub(crate) fn status(&self, state: &State, ui: &mut Ui) -> Response {
+        let reconnecting = state
+            .connections
+            .values()
+            .filter(|connection| matches!(connection, Connection::Reconnecting { .. }))
+            .count();
+        let connected = state.connections.len() - reconnecting;
+
+        let (color, text) = match (connected, reconnecting) {
+            (0, 0) => (ui.visuals().weak_text_color(), "No scanners".to_string()),
+            (connected, 0) => (Color32::GREEN, format!("{connected} connected")),
+            (connected, reconnecting) => (
+                Color32::YELLOW,
+                format!("{connected} connected, {reconnecting} reconnecting"),
+            ),
+        };
+
+        ui.add(Label::new(RichText::new(text).color(color)).sense(Sense::click()))
+            .on_hover_ui(|ui| {
+                if state.connections.is_empty() {
+                    ui.label("Open Scanner Setup to connect a device");
+                }
+
+                for (device, connection) in state.connections.iter() {
+                    ui.label(format!("{}: {}", device.name, connection.status()));
+                }
+            })
+    }
+
     pub(crate) fn render(&mut self, state: &mut State, ui: &mut Ui) {
-        ui.add_enabled_ui(!state.is_connected(), |ui| {
-            Grid::new("barcode_scanner_settings")
-                .num_columns(2)
-                .spacing([40.0, 4.0])
-                .show(ui, |ui| {
-                    self.settings_grid(state, ui);
+        Grid::new("barcode_scanner_settings")
+            .num_columns(2)
+            .spacing([40.0, 4.0])
+            .show(ui, |ui| {
+                self.settings_grid(state, ui);
+            });
+
+        if let Some(device) = state.selected_device.clone() {
+            if state.is_connected(&device) {
+                let can_send_commands = match (
+                    crate::barcode_scanner::source_for(&device.device_type),
+                    state.device_settings.get(&device),
+                ) {
+                    (Ok(source), Some(options)) => source.supports_commands(options),
+                    _ => false,
+                };
+
+                ui.horizontal(|ui| {
+                    if ui.button("Disconnect").clicked() {
+                        self.worker.send(Action::DisconnectDevice(device.clone()));
+                    }
+
+                    ui.add_enabled_ui(can_send_commands, |ui| {
+                        if ui.button("Trigger").clicked() {
+                            self.worker
+                                .send(Action::SendCommand(device, ScannerCommand::Trigger));
+                        }
+                    });
                 });
-        });
+            } else {
+                let can_connect = match (
+                    crate::barcode_scanner::source_for(&device.device_type),
+                    state.device_settings.get(&device),
+                ) {
+                    (Ok(source), Some(options)) => source.can_connect(options),
+                    _ => false,
+                };
 
-        if state.is_connected() {
-            if ui.button("Disconnect").clicked() {
-                self.worker.send(Action::DisconnectDevice);
+                ui.horizontal(|ui| {
+                    ui.add_enabled_ui(can_connect, |ui| {
+                        if ui.button("Connect").clicked() {
+                            self.worker.send(Action::ConnectDevice(device.clone()));
+                        }
+                    });
+
+                    if device.device_type.is_manual() && ui.button("Remove").clicked() {
+                        self.worker.send(Action::RemoveManualDevice(device));
+                    }
+                });
             }
-        } else {
-            ui.add_enabled_ui(state.can_connect(), |ui| {
-                if ui.button("Connect").clicked() {
-                    self.worker.send(Action::ConnectDevice);
+        }
+
+        ui.separator();
+
+        self.network_device_form(state, ui);
+        self.replay_device_form(state, ui);
+
+        ui.separator();
+
+        ui.label("Connected Devices");
+        if state.connections.is_empty() {
+            ui.weak("None");
+        }
+        for (device, connection) in state.connections.iter() {
+            ui.horizontal(|ui| {
+                if ui.small_button("Disconnect").clicked() {
+                    self.worker.send(Action::DisconnectDevice(device.clone()));
                 }
+                ui.label(&device.name);
+                ui.weak(connection.status());
             });
         }
 
@@ -203,6 +559,70 @@ impl BarcodeSettings {
         }
     }
 
+    fn network_device_form(&self, state: &mut State, ui: &mut Ui) {
+        ui.label("Add Network Device");
+        ui.horizontal(|ui| {
+            ComboBox::from_id_source("new_network_mode")
+                .selected_text(state.new_network_mode.to_string())
+                .show_ui(ui, |ui| {
+                    ui.style_mut().wrap = Some(false);
+                    for mode in enum_iterator::all::<NetworkMode>() {
+                        ui.selectable_value(&mut state.new_network_mode, mode, mode.to_string());
+                    }
+                });
+
+            let hint = match state.new_network_mode {
+                NetworkMode::TcpConnect => "host:port",
+                NetworkMode::TcpListen | NetworkMode::UdpListen => "0.0.0.0:port",
+            };
+            ui.add(
+                TextEdit::singleline(&mut state.new_network_address)
+                    .hint_text(hint)
+                    .desired_width(120.0),
+            );
+
+            let address = state.new_network_address.trim().to_string();
+            ui.add_enabled_ui(!address.is_empty(), |ui| {
+                if ui.button("Add").clicked() {
+                    let device = Device {
+                        name: format!("{} {address}", state.new_network_mode),
+                        device_type: DeviceType::Network {
+                            mode: state.new_network_mode,
+                            address,
+                        },
+                    };
+
+                    self.worker.send(Action::AddManualDevice(device));
+                    state.new_network_address.clear();
+                }
+            });
+        });
+    }
+
+    fn replay_device_form(&self, state: &mut State, ui: &mut Ui) {
+        ui.label("Add Capture Replay");
+        ui.horizontal(|ui| {
+            ui.add(
+                TextEdit::singleline(&mut state.new_replay_path)
+                    .hint_text("capture.jsonl")
+                    .desired_width(180.0),
+            );
+
+            let path = state.new_replay_path.trim().to_string();
+            ui.add_enabled_ui(!path.is_empty(), |ui| {
+                if ui.button("Add").clicked() {
+                    let device = Device {
+                        name: format!("Replay {path}"),
+                        device_type: DeviceType::Replay { path },
+                    };
+
+                    self.worker.send(Action::AddManualDevice(device));
+                    state.new_replay_path.clear();
+                }
+            });
+        });
+    }
+
     fn settings_grid(&self, state: &mut State, ui: &mut Ui) {
         ui.label("Devices");
         ComboBox::from_label("Devices")
@@ -226,32 +646,91 @@ impl BarcodeSettings {
             });
         ui.end_row();
 
-        ui.label("HID Type");
-        ui.add_enabled_ui(state.is_hid_device(), |ui| {
-            ComboBox::from_label("HID Type")
-                .selected_text(state.hid_type.to_string())
-                .show_ui(ui, |ui| {
-                    ui.style_mut().wrap = Some(false);
-                    ui.set_min_width(60.0);
-                    for hid_type in enum_iterator::all::<HidType>() {
-                        ui.selectable_value(&mut state.hid_type, hid_type, hid_type.to_string());
-                    }
-                });
+        for error in &state.device_errors {
+            ui.label("");
+            ui.colored_label(ui.visuals().error_fg_color, error);
+            ui.end_row();
+        }
+
+        let Some(device) = state.selected_device.clone() else {
+            return;
+        };
+
+        // Settings can't be changed while a device is connected.
+        ui.set_enabled(!state.is_connected(&device));
+
+        let settings = state
+            .device_settings
+            .entry(device.clone())
+            .or_insert_with(|| default_options(&device));
+
+        if let Ok(source) = crate::barcode_scanner::source_for(&device.device_type) {
+            source.settings(&device, settings, ui);
+        }
+
+        self.framing_grid(&mut settings.framing, ui);
+    }
+
+    fn framing_grid(&self, framing: &mut FramingConfig, ui: &mut Ui) {
+        ui.label("Idle Timeout");
+        ui.horizontal(|ui| {
+            let mut enabled = framing.idle_timeout_ms.is_some();
+            if ui.checkbox(&mut enabled, "").changed() {
+                framing.idle_timeout_ms = enabled.then_some(50);
+            }
+
+            if let Some(idle_timeout_ms) = framing.idle_timeout_ms.as_mut() {
+                ui.add(
+                    DragValue::new(idle_timeout_ms)
+                        .clamp_range(1..=10_000)
+                        .suffix(" ms"),
+                );
+            }
         });
         ui.end_row();
 
-        ui.label("Baud Rate");
-        ui.add_enabled_ui(state.is_serial_device(), |ui| {
-            ComboBox::from_label("Baud Rate")
-                .selected_text(state.baud_rate.to_string())
-                .show_ui(ui, |ui| {
-                    ui.style_mut().wrap = Some(false);
-                    ui.set_min_width(60.0);
-                    for rate in [9600, 115_200] {
-                        ui.selectable_value(&mut state.baud_rate, rate, rate.to_string());
+        ui.label("Terminators");
+        ui.horizontal(|ui| {
+            for terminator in enum_iterator::all::<Terminator>() {
+                let mut enabled = framing.terminators.contains(&terminator);
+                if ui.checkbox(&mut enabled, terminator.to_string()).changed() {
+                    if enabled {
+                        framing.terminators.push(terminator);
+                    } else {
+                        framing.terminators.retain(|t| *t != terminator);
                     }
-                });
+                }
+            }
         });
         ui.end_row();
+
+        ui.label("STX/ETX Envelope");
+        ui.checkbox(&mut framing.stx_etx_envelope, "");
+        ui.end_row();
+
+        ui.label("Strip Prefix");
+        ui.add(TextEdit::singleline(&mut framing.prefix).desired_width(120.0));
+        ui.end_row();
+
+        ui.label("Strip Suffix");
+        ui.add(TextEdit::singleline(&mut framing.suffix).desired_width(120.0));
+        ui.end_row();
+
+        ui.label("Max Length");
+        ui.add(
+            DragValue::new(&mut framing.max_length).custom_formatter(|value, _| {
+                if value == 0.0 {
+                    "Unlimited".to_string()
+                } else {
+                    value.to_string()
+                }
+            }),
+        );
+        ui.end_row();
+
+        ui.label("Part Counter")
+            .on_hover_text("Combine scans starting with [1/3], [2/3], and so on");
+        ui.checkbox(&mut framing.part_counter, "");
+        ui.end_row();
     }
 }